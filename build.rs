@@ -0,0 +1,5 @@
+fn main() {
+    // The GraphQL queries are read at compile time by graphql_client
+    println!("cargo::rerun-if-changed=schema.gql");
+    println!("cargo::rerun-if-changed=src/graphql");
+}
@@ -0,0 +1,1677 @@
+use std::{
+    collections::BTreeSet,
+    fmt::Display,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use futures::{Stream, StreamExt, stream::BoxStream};
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+use graphql_client::{GraphQLQuery, Response};
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use url::Url;
+use uuid::Uuid;
+
+mod rest_types;
+mod serde_utils;
+mod transport;
+
+use rest_types::{AbortChunkedUploadRequest, InitChunkedUploadRequest, InitChunkedUploadResponse};
+pub use transport::{HttpTransport, MockTransport, Transport};
+
+const MEGABYTE: u64 = 1024 * 1024; // 1MB
+pub const MIN_CHUNK_SIZE_BYTES: u64 = 5 * MEGABYTE;
+const MAX_CHUNK_SIZE_BYTES: u64 = 100 * MEGABYTE;
+const MAX_PARTS_COUNT: u64 = 10000;
+
+const SIZE_500MB: u64 = 500 * MEGABYTE;
+const SIZE_10GB: u64 = 10 * 1024 * MEGABYTE;
+const SIZE_50GB: u64 = 50 * 1024 * MEGABYTE;
+
+pub const DEFAULT_UPLOAD_CONCURRENCY: usize = 4;
+
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Requests that carry a file, such as upload parts of up to 100MB, or that process one, get
+/// much longer than `DEFAULT_REQUEST_TIMEOUT` to finish.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+const QUERY_MAX_ATTEMPTS: u32 = 5;
+const QUERY_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+const FINALIZE_MAX_ATTEMPTS: u32 = 5;
+const FINALIZE_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+#[derive(Error, Debug)]
+pub enum AdaptiveError {
+    #[error("HTTP error: {0}")]
+    HttpError(#[from] reqwest::Error),
+
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("URL parse error: {0}")]
+    UrlParseError(#[from] url::ParseError),
+
+    #[error("File too small for chunked upload: {size} bytes (minimum: {min_size} bytes)")]
+    FileTooSmall { size: u64, min_size: u64 },
+
+    #[error("File too large: {size} bytes exceeds maximum {max_size} bytes")]
+    FileTooLarge { size: u64, max_size: u64 },
+
+    #[error("GraphQL errors: {0:?}")]
+    GraphQLErrors(Vec<graphql_client::Error>),
+
+    #[error("No data returned from GraphQL")]
+    NoGraphQLData,
+
+    #[error("Job not found: {0}")]
+    JobNotFound(Uuid),
+
+    #[error("Failed to initialize chunked upload: {status} - {body}")]
+    ChunkedUploadInitFailed { status: String, body: String },
+
+    #[error("Failed to upload part {part_number}: {status} - {body}")]
+    ChunkedUploadPartFailed {
+        part_number: u64,
+        status: String,
+        body: String,
+    },
+
+    #[error("Upload source did not match its reported size of {size} bytes")]
+    SourceSizeMismatch { size: u64 },
+
+    #[error("Failed to create dataset: {0}")]
+    DatasetCreationFailed(String),
+
+    #[error("No mock response found at {0}")]
+    MockResponseMissing(PathBuf),
+
+    #[error("Request failed after {attempts} attempts")]
+    RetriesExhausted {
+        attempts: u32,
+        source: Box<AdaptiveError>,
+    },
+
+    #[error("HTTP status error: {status} - {body}")]
+    HttpStatusError { status: String, body: String },
+
+    #[error("Failed to parse JSON response: {error}. Body preview: {body}")]
+    JsonParseError { error: String, body: String },
+}
+
+impl AdaptiveError {
+    /// Whether the error is likely to go away if the request is retried.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            AdaptiveError::HttpError(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+            AdaptiveError::HttpStatusError { status, .. } => status.starts_with('5'),
+            _ => false,
+        }
+    }
+
+    /// Whether the server refused the request because of the API key.
+    pub fn is_unauthenticated(&self) -> bool {
+        match self {
+            AdaptiveError::HttpStatusError { status, .. } => {
+                status.starts_with("401") || status.starts_with("403")
+            }
+            AdaptiveError::GraphQLErrors(errors) => errors.iter().any(|error| {
+                error
+                    .extensions
+                    .as_ref()
+                    .and_then(|extensions| extensions.get("code"))
+                    .and_then(Value::as_str)
+                    .is_some_and(|code| {
+                        matches!(
+                            code.to_ascii_uppercase().as_str(),
+                            "UNAUTHENTICATED" | "UNAUTHORIZED" | "FORBIDDEN"
+                        )
+                    })
+            }),
+            _ => false,
+        }
+    }
+}
+
+type Result<T> = std::result::Result<T, AdaptiveError>;
+
+#[derive(Clone, Debug, Default)]
+pub struct ChunkedUploadProgress {
+    pub bytes_uploaded: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadProgress {
+    pub bytes_downloaded: u64,
+    pub total_bytes: Option<u64>,
+}
+
+/// Which jobs to list, all of them when left at the default.
+#[derive(Debug, Clone, Default)]
+pub struct JobFilter {
+    pub project: Option<String>,
+    pub kinds: Option<Vec<list_jobs::JobKind>>,
+    pub statuses: Option<Vec<list_jobs::JobStatus>>,
+    /// Only jobs newer than this one
+    pub since_id: Option<Uuid>,
+    pub created_after: Option<SystemTime>,
+}
+
+/// The state of a chunked upload session, enough to resume it after an interruption.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UploadSession {
+    pub session_id: String,
+    pub chunk_size: u64,
+    pub total_parts: u64,
+    pub uploaded_parts: BTreeSet<u64>,
+}
+
+#[derive(Debug)]
+pub enum UploadEvent {
+    Progress(ChunkedUploadProgress),
+    /// Emitted once the session is known and after every part uploaded
+    Session(UploadSession),
+    Complete(
+        create_dataset_from_multipart::CreateDatasetFromMultipartCreateDatasetFromMultipartUpload,
+    ),
+}
+
+/// Datasets are assumed to be JSONL when their type is not known otherwise.
+pub const DEFAULT_DATASET_EXTENSION: &str = "jsonl";
+
+/// The dataset file extensions the platform accepts and their content types, the default first.
+const DATASET_TYPES: &[(&str, &str)] = &[
+    (DEFAULT_DATASET_EXTENSION, "application/jsonl"),
+    ("json", "application/json"),
+    ("csv", "text/csv"),
+    ("parquet", "application/vnd.apache.parquet"),
+    ("txt", "text/plain"),
+];
+
+/// The extension of a dataset file if it is one of the known dataset types.
+pub fn dataset_extension(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    DATASET_TYPES
+        .iter()
+        .find(|(known, _)| *known == extension)
+        .map(|(known, _)| *known)
+}
+
+/// The content type of a dataset file going by its extension, assuming JSONL when unknown.
+pub fn dataset_content_type(path: &Path) -> &'static str {
+    let extension = dataset_extension(path);
+    DATASET_TYPES
+        .iter()
+        .find(|(known, _)| Some(*known) == extension)
+        .unwrap_or(&DATASET_TYPES[0])
+        .1
+}
+
+pub fn calculate_upload_parts(file_size: u64) -> Result<(u64, u64)> {
+    if file_size < MIN_CHUNK_SIZE_BYTES {
+        return Err(AdaptiveError::FileTooSmall {
+            size: file_size,
+            min_size: MIN_CHUNK_SIZE_BYTES,
+        });
+    }
+
+    let mut chunk_size = if file_size < SIZE_500MB {
+        5 * MEGABYTE
+    } else if file_size < SIZE_10GB {
+        10 * MEGABYTE
+    } else if file_size < SIZE_50GB {
+        50 * MEGABYTE
+    } else {
+        100 * MEGABYTE
+    };
+
+    let mut total_parts = file_size.div_ceil(chunk_size);
+
+    if total_parts > MAX_PARTS_COUNT {
+        chunk_size = file_size.div_ceil(MAX_PARTS_COUNT);
+
+        if chunk_size > MAX_CHUNK_SIZE_BYTES {
+            let max_file_size = MAX_CHUNK_SIZE_BYTES * MAX_PARTS_COUNT;
+            return Err(AdaptiveError::FileTooLarge {
+                size: file_size,
+                max_size: max_file_size,
+            });
+        }
+
+        total_parts = file_size.div_ceil(chunk_size);
+    }
+
+    Ok((total_parts, chunk_size))
+}
+
+fn retry_backoff(attempt: u32) -> Duration {
+    // Spread out retries of concurrent requests, the clock is random enough for that
+    let jitter_ms = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos()
+        % 250;
+    QUERY_INITIAL_BACKOFF * 2u32.pow(attempt - 1) + Duration::from_millis(jitter_ms as u64)
+}
+
+type IdOrKey = String;
+#[allow(clippy::upper_case_acronyms)]
+type UUID = Uuid;
+type JsObject = Map<String, Value>;
+type InputDatetime = String;
+type DateTime = String;
+#[allow(clippy::upper_case_acronyms)]
+type JSON = Value;
+type KeyInput = String;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestamp(pub SystemTime);
+
+impl<'de> serde::Deserialize<'de> for Timestamp {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let system_time = serde_utils::deserialize_timestamp_millis(deserializer)?;
+        Ok(Timestamp(system_time))
+    }
+}
+
+const PAGE_SIZE: usize = 20;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Upload(usize);
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/list.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct GetCustomRecipes;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/job.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct GetJob;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/datasets.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct ListDatasets;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/dataset.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct GetDataset;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/job_artifacts.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct GetJobArtifacts;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/jobs.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct ListJobs;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/cancel.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct CancelJob;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/models.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct ListModels;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/all_models.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct ListAllModels;
+
+impl Display for get_job::JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            get_job::JobStatus::PENDING => write!(f, "Pending"),
+            get_job::JobStatus::RUNNING => write!(f, "Running"),
+            get_job::JobStatus::COMPLETED => write!(f, "Completed"),
+            get_job::JobStatus::FAILED => write!(f, "Failed"),
+            get_job::JobStatus::CANCELED => write!(f, "Canceled"),
+            get_job::JobStatus::Other(_) => write!(f, "Unknown"),
+        }
+    }
+}
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/publish.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct PublishCustomRecipe;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/update_recipe.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct UpdateCustomRecipe;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/upload_dataset.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct UploadDataset;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/create_dataset_from_multipart.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct CreateDatasetFromMultipart;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/run.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct RunCustomRecipe;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/projects.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct ListProjects;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/me.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct Me;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/pools.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct ListComputePools;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/recipe.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct GetRecipe;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/roles.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct ListRoles;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/create_role.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct CreateRole;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/update_role.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct UpdateRole;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/teams.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct ListTeams;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/create_team.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct CreateTeam;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/users.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct ListUsers;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/create_user.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct CreateUser;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/delete_user.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct DeleteUser;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/add_team_member.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct AddTeamMember;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "schema.gql",
+    query_path = "src/graphql/remove_team_member.graphql",
+    response_derives = "Debug, Clone"
+)]
+pub struct RemoveTeamMember;
+
+const INIT_CHUNKED_UPLOAD_ROUTE: &str = "v1/upload/init";
+const UPLOAD_PART_ROUTE: &str = "v1/upload/part";
+const ABORT_CHUNKED_UPLOAD_ROUTE: &str = "v1/upload/abort";
+/// The name transports see for file downloads, which have no fixed route
+const DOWNLOAD: &str = "download";
+
+#[derive(Clone)]
+pub struct AdaptiveClient {
+    client: Client,
+    transport: Arc<dyn Transport>,
+    graphql_url: Url,
+    rest_base_url: Url,
+    auth_token: String,
+    request_timeout: Duration,
+}
+
+fn build_http_client(connect_timeout: Duration) -> Client {
+    Client::builder()
+        .user_agent(format!(
+            "adaptive-client-rust/{}",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .connect_timeout(connect_timeout)
+        .build()
+        .expect("Failed to build HTTP client")
+}
+
+impl AdaptiveClient {
+    pub fn new(api_base_url: Url, auth_token: String) -> Self {
+        let graphql_url = api_base_url
+            .join("graphql")
+            .expect("Failed to append graphql to base URL");
+
+        Self {
+            client: build_http_client(DEFAULT_CONNECT_TIMEOUT),
+            transport: Arc::new(HttpTransport),
+            graphql_url,
+            rest_base_url: api_base_url,
+            auth_token,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    /// Set how long API requests may take in total and how long connecting may take.
+    /// File uploads and downloads are not bound by the request timeout.
+    pub fn with_timeouts(mut self, request_timeout: Duration, connect_timeout: Duration) -> Self {
+        self.client = build_http_client(connect_timeout);
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    pub fn with_graphql_url(mut self, graphql_url: Url) -> Self {
+        self.graphql_url = graphql_url;
+        self
+    }
+
+    pub fn with_rest_base_url(mut self, rest_base_url: Url) -> Self {
+        self.rest_base_url = rest_base_url;
+        self
+    }
+
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Serve all requests from canned responses in `mock_dir` instead of the network, see
+    /// [`MockTransport`].
+    pub fn with_mock_dir(self, mock_dir: PathBuf) -> Self {
+        self.with_transport(MockTransport::new(mock_dir))
+    }
+
+    async fn execute_query<T>(&self, _query: T, variables: T::Variables) -> Result<T::ResponseData>
+    where
+        T: GraphQLQuery,
+        T::Variables: serde::Serialize,
+        T::ResponseData: DeserializeOwned,
+    {
+        let request_body = T::build_query(variables);
+
+        let request = self
+            .client
+            .post(self.graphql_url.clone())
+            .bearer_auth(&self.auth_token)
+            .timeout(self.request_timeout)
+            .json(&request_body);
+
+        let mut attempt = 1;
+        loop {
+            let attempt_request = request
+                .try_clone()
+                .expect("GraphQL requests with a JSON body can be cloned");
+            match self
+                .send_graphql(request_body.operation_name, attempt_request)
+                .await
+            {
+                Err(e) if e.is_transient() && attempt < QUERY_MAX_ATTEMPTS => {
+                    tokio::time::sleep(retry_backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) if attempt > 1 => {
+                    return Err(AdaptiveError::RetriesExhausted {
+                        attempts: attempt,
+                        source: Box::new(e),
+                    });
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Like `execute_query`, but never retried since mutations are not idempotent.
+    async fn execute_mutation<T>(
+        &self,
+        _query: T,
+        variables: T::Variables,
+    ) -> Result<T::ResponseData>
+    where
+        T: GraphQLQuery,
+        T::Variables: serde::Serialize,
+        T::ResponseData: DeserializeOwned,
+    {
+        let request_body = T::build_query(variables);
+
+        let request = self
+            .client
+            .post(self.graphql_url.clone())
+            .bearer_auth(&self.auth_token)
+            .timeout(self.request_timeout)
+            .json(&request_body);
+
+        self.send_graphql(request_body.operation_name, request)
+            .await
+    }
+
+    async fn send_graphql<R: DeserializeOwned>(
+        &self,
+        operation_name: &str,
+        request: RequestBuilder,
+    ) -> Result<R> {
+        let response = self.transport.send(operation_name, request).await?;
+
+        let status = response.status();
+        let response_text = response.text().await?;
+
+        if !status.is_success() {
+            return Err(AdaptiveError::HttpStatusError {
+                status: status.to_string(),
+                body: response_text,
+            });
+        }
+
+        let response_body: Response<R> =
+            serde_json::from_str(&response_text).map_err(|e| AdaptiveError::JsonParseError {
+                error: e.to_string(),
+                body: response_text.chars().take(500).collect(),
+            })?;
+
+        match response_body.data {
+            Some(data) => Ok(data),
+            None => {
+                if let Some(errors) = response_body.errors {
+                    return Err(AdaptiveError::GraphQLErrors(errors));
+                }
+                Err(AdaptiveError::NoGraphQLData)
+            }
+        }
+    }
+
+    pub async fn list_recipes(
+        &self,
+        project: &str,
+    ) -> Result<Vec<get_custom_recipes::GetCustomRecipesCustomRecipes>> {
+        let variables = get_custom_recipes::Variables {
+            project: IdOrKey::from(project),
+        };
+
+        let response_data = self.execute_query(GetCustomRecipes, variables).await?;
+        Ok(response_data.custom_recipes)
+    }
+
+    pub async fn get_job(&self, job_id: Uuid) -> Result<get_job::GetJobJob> {
+        let variables = get_job::Variables { id: job_id };
+
+        let response_data = self.execute_query(GetJob, variables).await?;
+
+        match response_data.job {
+            Some(job) => Ok(job),
+            None => Err(AdaptiveError::JobNotFound(job_id)),
+        }
+    }
+
+    pub async fn get_job_artifacts(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<get_job_artifacts::GetJobArtifactsJobArtifacts>> {
+        let variables = get_job_artifacts::Variables { id: job_id };
+
+        let response_data = self.execute_query(GetJobArtifacts, variables).await?;
+
+        match response_data.job {
+            Some(job) => Ok(job.artifacts),
+            None => Err(AdaptiveError::JobNotFound(job_id)),
+        }
+    }
+
+    pub async fn upload_dataset<P: AsRef<Path>>(
+        &self,
+        project: &str,
+        name: &str,
+        dataset: P,
+        content_type: &str,
+    ) -> Result<upload_dataset::UploadDatasetCreateDataset> {
+        let dataset_file = reqwest::multipart::Part::file(dataset)
+            .await?
+            .mime_str(content_type)?;
+        self.upload_dataset_part(project, name, dataset_file).await
+    }
+
+    pub async fn upload_dataset_bytes(
+        &self,
+        project: &str,
+        name: &str,
+        file_name: String,
+        data: Vec<u8>,
+        content_type: &str,
+    ) -> Result<upload_dataset::UploadDatasetCreateDataset> {
+        let dataset_file = reqwest::multipart::Part::bytes(data)
+            .file_name(file_name)
+            .mime_str(content_type)?;
+        self.upload_dataset_part(project, name, dataset_file).await
+    }
+
+    async fn upload_dataset_part(
+        &self,
+        project: &str,
+        name: &str,
+        dataset_file: reqwest::multipart::Part,
+    ) -> Result<upload_dataset::UploadDatasetCreateDataset> {
+        let variables = upload_dataset::Variables {
+            project: IdOrKey::from(project),
+            file: Upload(0),
+            name: Some(name.to_string()),
+        };
+
+        let operations = UploadDataset::build_query(variables);
+        let operation_name = operations.operation_name;
+        let operations = serde_json::to_string(&operations)?;
+
+        let file_map = r#"{ "0": ["variables.file"] }"#;
+
+        let form = reqwest::multipart::Form::new()
+            .text("operations", operations)
+            .text("map", file_map)
+            .part("0", dataset_file);
+
+        let request = self
+            .client
+            .post(self.graphql_url.clone())
+            .bearer_auth(&self.auth_token)
+            .timeout(UPLOAD_TIMEOUT)
+            .multipart(form);
+
+        let response_data: <UploadDataset as graphql_client::GraphQLQuery>::ResponseData =
+            self.send_graphql(operation_name, request).await?;
+        Ok(response_data.create_dataset)
+    }
+
+    pub async fn publish_recipe<P: AsRef<Path>>(
+        &self,
+        project: &str,
+        name: &str,
+        key: &str,
+        recipe: P,
+    ) -> Result<publish_custom_recipe::PublishCustomRecipeCreateCustomRecipe> {
+        let variables = publish_custom_recipe::Variables {
+            project: IdOrKey::from(project),
+            file: Upload(0),
+            name: Some(name.to_string()),
+            key: Some(key.to_string()),
+        };
+
+        let operations = PublishCustomRecipe::build_query(variables);
+        let operation_name = operations.operation_name;
+        let operations = serde_json::to_string(&operations)?;
+
+        let file_map = r#"{ "0": ["variables.file"] }"#;
+
+        let recipe_file = reqwest::multipart::Part::file(recipe).await?;
+
+        let form = reqwest::multipart::Form::new()
+            .text("operations", operations)
+            .text("map", file_map)
+            .part("0", recipe_file);
+
+        let request = self
+            .client
+            .post(self.graphql_url.clone())
+            .bearer_auth(&self.auth_token)
+            .timeout(UPLOAD_TIMEOUT)
+            .multipart(form);
+
+        let response_data: <PublishCustomRecipe as graphql_client::GraphQLQuery>::ResponseData =
+            self.send_graphql(operation_name, request).await?;
+        Ok(response_data.create_custom_recipe)
+    }
+
+    pub async fn update_recipe<P: AsRef<Path>>(
+        &self,
+        project: &str,
+        id: &str,
+        name: Option<String>,
+        description: Option<String>,
+        labels: Option<Vec<update_custom_recipe::LabelInput>>,
+        recipe_file: Option<P>,
+    ) -> Result<update_custom_recipe::UpdateCustomRecipeUpdateCustomRecipe> {
+        let input = update_custom_recipe::UpdateRecipeInput {
+            name,
+            description,
+            labels,
+        };
+
+        match recipe_file {
+            Some(file_path) => {
+                let variables = update_custom_recipe::Variables {
+                    project: IdOrKey::from(project),
+                    id: IdOrKey::from(id),
+                    input,
+                    file: Some(Upload(0)),
+                };
+
+                let operations = UpdateCustomRecipe::build_query(variables);
+                let operation_name = operations.operation_name;
+                let operations = serde_json::to_string(&operations)?;
+
+                let file_map = r#"{ "0": ["variables.file"] }"#;
+
+                let recipe_file = reqwest::multipart::Part::file(file_path).await?;
+
+                let form = reqwest::multipart::Form::new()
+                    .text("operations", operations)
+                    .text("map", file_map)
+                    .part("0", recipe_file);
+
+                let request = self
+                    .client
+                    .post(self.graphql_url.clone())
+                    .bearer_auth(&self.auth_token)
+                    .timeout(UPLOAD_TIMEOUT)
+                    .multipart(form);
+
+                let response_data: <UpdateCustomRecipe as graphql_client::GraphQLQuery>::ResponseData =
+                    self.send_graphql(operation_name, request).await?;
+                Ok(response_data.update_custom_recipe)
+            }
+            None => {
+                let variables = update_custom_recipe::Variables {
+                    project: IdOrKey::from(project),
+                    id: IdOrKey::from(id),
+                    input,
+                    file: None,
+                };
+
+                let response_data = self.execute_mutation(UpdateCustomRecipe, variables).await?;
+                Ok(response_data.update_custom_recipe)
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn run_recipe(
+        &self,
+        project: &str,
+        recipe_id: &str,
+        parameters: Map<String, Value>,
+        name: Option<String>,
+        compute_pool: Option<String>,
+        num_gpus: u32,
+        use_experimental_runner: bool,
+    ) -> Result<run_custom_recipe::RunCustomRecipeCreateJob> {
+        let variables = run_custom_recipe::Variables {
+            input: run_custom_recipe::JobInput {
+                recipe: recipe_id.to_string(),
+                project: project.to_string(),
+                args: parameters,
+                name,
+                compute_pool,
+                num_gpus: num_gpus as i64,
+                use_experimental_runner,
+                image_tag: None,
+                max_cpu: None,
+                max_ram_gb: None,
+                max_duration_secs: None,
+                resume_artifact_id: None,
+            },
+        };
+
+        let response_data = self.execute_mutation(RunCustomRecipe, variables).await?;
+        Ok(response_data.create_job)
+    }
+
+    /// List the jobs matching `filter`, newest first. When `filter.since_id` is set, pagination
+    /// stops once that job (or any job ordered before it) is reached, so only newer jobs are
+    /// returned. At most `limit` jobs are fetched, all of them when it is `None`.
+    pub async fn list_jobs(
+        &self,
+        filter: &JobFilter,
+        limit: Option<usize>,
+    ) -> Result<Vec<list_jobs::ListJobsJobsNodes>> {
+        let mut jobs = Vec::new();
+        let mut after = None;
+        loop {
+            let page = self.list_jobs_page(filter, after).await?;
+            for job in page.nodes {
+                if filter.since_id.is_some_and(|since_id| job.id <= since_id)
+                    || limit.is_some_and(|limit| jobs.len() >= limit)
+                {
+                    return Ok(jobs);
+                }
+                jobs.push(job);
+            }
+            if !page.page_info.has_next_page {
+                return Ok(jobs);
+            }
+            after = page.page_info.end_cursor;
+        }
+    }
+
+    async fn list_jobs_page(
+        &self,
+        filter: &JobFilter,
+        after: Option<String>,
+    ) -> Result<list_jobs::ListJobsJobs> {
+        let timerange = filter
+            .created_after
+            .map(|created_after| list_jobs::TimeRange {
+                from: humantime::format_rfc3339_seconds(created_after).to_string(),
+                to: "now".to_string(),
+            });
+        let variables = list_jobs::Variables {
+            filter: Some(list_jobs::ListJobsFilterInput {
+                project: filter.project.clone(),
+                kind: filter.kinds.clone(),
+                status: filter.statuses.clone(),
+                timerange,
+                custom_recipes: None,
+                artifacts: None,
+                created_by: None,
+                name: None,
+                advanced_filter: Box::new(None),
+            }),
+            cursor: Some(list_jobs::CursorPageInput {
+                first: Some(PAGE_SIZE as i64),
+                after,
+                before: None,
+                last: None,
+                offset: None,
+            }),
+        };
+
+        let response_data = self.execute_query(ListJobs, variables).await?;
+        Ok(response_data.jobs)
+    }
+
+    pub async fn cancel_job(&self, job_id: Uuid) -> Result<cancel_job::CancelJobCancelJob> {
+        let variables = cancel_job::Variables { job_id };
+
+        let response_data = self.execute_mutation(CancelJob, variables).await?;
+        Ok(response_data.cancel_job)
+    }
+
+    pub async fn list_models(
+        &self,
+        project: String,
+    ) -> Result<Vec<list_models::ListModelsProjectModelServices>> {
+        let variables = list_models::Variables { project };
+
+        let response_data = self.execute_query(ListModels, variables).await?;
+        Ok(response_data
+            .project
+            .map(|project| project.model_services)
+            .unwrap_or(Vec::new()))
+    }
+
+    pub async fn list_all_models(&self) -> Result<Vec<list_all_models::ListAllModelsModels>> {
+        let variables = list_all_models::Variables {};
+
+        let response_data = self.execute_query(ListAllModels, variables).await?;
+        Ok(response_data.models)
+    }
+
+    pub async fn list_projects(&self) -> Result<Vec<list_projects::ListProjectsProjects>> {
+        let variables = list_projects::Variables {};
+
+        let response_data = self.execute_query(ListProjects, variables).await?;
+        Ok(response_data.projects)
+    }
+
+    pub async fn me(&self) -> Result<Option<me::MeMe>> {
+        let variables = me::Variables {};
+
+        let response_data = self.execute_query(Me, variables).await?;
+        Ok(response_data.me)
+    }
+
+    /// Like [`Self::me`], but a single attempt that fails once `timeout` has passed, for
+    /// checking quickly whether the API can be used at all.
+    pub async fn check_me(&self, timeout: Duration) -> Result<Option<me::MeMe>> {
+        let request_body = Me::build_query(me::Variables {});
+
+        let request = self
+            .client
+            .post(self.graphql_url.clone())
+            .bearer_auth(&self.auth_token)
+            .timeout(timeout)
+            .json(&request_body);
+
+        let response_data: me::ResponseData = self
+            .send_graphql(request_body.operation_name, request)
+            .await?;
+        Ok(response_data.me)
+    }
+
+    pub async fn list_pools(
+        &self,
+    ) -> Result<Vec<list_compute_pools::ListComputePoolsComputePools>> {
+        let variables = list_compute_pools::Variables {};
+
+        let response_data = self.execute_query(ListComputePools, variables).await?;
+        Ok(response_data.compute_pools)
+    }
+
+    pub async fn list_roles(&self) -> Result<Vec<list_roles::ListRolesRoles>> {
+        let variables = list_roles::Variables {};
+
+        let response_data = self.execute_query(ListRoles, variables).await?;
+        Ok(response_data.roles)
+    }
+
+    pub async fn create_role(
+        &self,
+        name: &str,
+        key: Option<&str>,
+        permissions: Vec<String>,
+    ) -> Result<create_role::CreateRoleCreateRole> {
+        let variables = create_role::Variables {
+            input: create_role::RoleCreate {
+                name: name.to_string(),
+                key: key.map(|k| k.to_string()),
+                permissions,
+            },
+        };
+
+        let response_data = self.execute_mutation(CreateRole, variables).await?;
+        Ok(response_data.create_role)
+    }
+
+    pub async fn update_role(
+        &self,
+        role: &str,
+        name: Option<&str>,
+        permissions: Option<Vec<String>>,
+    ) -> Result<update_role::UpdateRoleUpdateRole> {
+        let variables = update_role::Variables {
+            input: update_role::RoleUpdate {
+                role: role.to_string(),
+                name: name.map(|n| n.to_string()),
+                permissions,
+            },
+        };
+
+        let response_data = self.execute_mutation(UpdateRole, variables).await?;
+        Ok(response_data.update_role)
+    }
+
+    pub async fn list_teams(&self) -> Result<Vec<list_teams::ListTeamsTeams>> {
+        let variables = list_teams::Variables {};
+
+        let response_data = self.execute_query(ListTeams, variables).await?;
+        Ok(response_data.teams)
+    }
+
+    pub async fn create_team(
+        &self,
+        name: &str,
+        key: Option<&str>,
+    ) -> Result<create_team::CreateTeamCreateTeam> {
+        let variables = create_team::Variables {
+            input: create_team::TeamCreate {
+                name: name.to_string(),
+                key: key.map(|k| k.to_string()),
+            },
+        };
+
+        let response_data = self.execute_mutation(CreateTeam, variables).await?;
+        Ok(response_data.create_team)
+    }
+
+    pub async fn list_users(&self) -> Result<Vec<list_users::ListUsersUsers>> {
+        let variables = list_users::Variables {};
+
+        let response_data = self.execute_query(ListUsers, variables).await?;
+        Ok(response_data.users)
+    }
+
+    pub async fn create_user(
+        &self,
+        name: &str,
+        email: Option<&str>,
+        teams: Vec<create_user::UserCreateTeamWithRole>,
+        user_type: Option<create_user::UserType>,
+        generate_api_key: Option<bool>,
+    ) -> Result<create_user::CreateUserCreateUser> {
+        let variables = create_user::Variables {
+            input: create_user::UserCreate {
+                name: name.to_string(),
+                email: email.map(|e| e.to_string()),
+                teams,
+                user_type: user_type.unwrap_or(create_user::UserType::HUMAN),
+                generate_api_key,
+            },
+        };
+
+        let response_data = self.execute_mutation(CreateUser, variables).await?;
+        Ok(response_data.create_user)
+    }
+
+    pub async fn delete_user(&self, user: &str) -> Result<delete_user::DeleteUserDeleteUser> {
+        let variables = delete_user::Variables {
+            user: user.to_string(),
+        };
+
+        let response_data = self.execute_mutation(DeleteUser, variables).await?;
+        Ok(response_data.delete_user)
+    }
+
+    pub async fn add_team_member(
+        &self,
+        user: &str,
+        team: &str,
+        role: &str,
+    ) -> Result<add_team_member::AddTeamMemberSetTeamMember> {
+        let variables = add_team_member::Variables {
+            input: add_team_member::TeamMemberSet {
+                user: user.to_string(),
+                team: team.to_string(),
+                role: role.to_string(),
+            },
+        };
+
+        let response_data = self.execute_mutation(AddTeamMember, variables).await?;
+        Ok(response_data.set_team_member)
+    }
+
+    pub async fn remove_team_member(
+        &self,
+        user: &str,
+        team: &str,
+    ) -> Result<remove_team_member::RemoveTeamMemberRemoveTeamMember> {
+        let variables = remove_team_member::Variables {
+            input: remove_team_member::TeamMemberRemove {
+                user: user.to_string(),
+                team: team.to_string(),
+            },
+        };
+
+        let response_data = self.execute_mutation(RemoveTeamMember, variables).await?;
+        Ok(response_data.remove_team_member)
+    }
+
+    pub async fn get_recipe(
+        &self,
+        project: String,
+        id_or_key: String,
+    ) -> Result<Option<get_recipe::GetRecipeCustomRecipe>> {
+        let variables = get_recipe::Variables { project, id_or_key };
+
+        let response_data = self.execute_query(GetRecipe, variables).await?;
+        Ok(response_data.custom_recipe)
+    }
+
+    pub async fn list_datasets(
+        &self,
+        project: &str,
+    ) -> Result<Vec<list_datasets::ListDatasetsDatasets>> {
+        let variables = list_datasets::Variables {
+            project: IdOrKey::from(project),
+        };
+
+        let response_data = self.execute_query(ListDatasets, variables).await?;
+        Ok(response_data.datasets)
+    }
+
+    pub async fn get_dataset(
+        &self,
+        project: &str,
+        id_or_key: &str,
+    ) -> Result<Option<get_dataset::GetDatasetDataset>> {
+        let variables = get_dataset::Variables {
+            project: project.to_string(),
+            id_or_key: id_or_key.to_string(),
+        };
+
+        let response_data = self.execute_query(GetDataset, variables).await?;
+        Ok(response_data.dataset)
+    }
+
+    async fn init_chunked_upload(&self, total_parts: u64, content_type: &str) -> Result<String> {
+        let url = self.rest_base_url.join(INIT_CHUNKED_UPLOAD_ROUTE)?;
+
+        let request = InitChunkedUploadRequest {
+            content_type: content_type.to_string(),
+            metadata: None,
+            total_parts_count: total_parts,
+        };
+
+        let request = self
+            .client
+            .post(url)
+            .bearer_auth(&self.auth_token)
+            .timeout(self.request_timeout)
+            .json(&request);
+        let response = self
+            .transport
+            .send(INIT_CHUNKED_UPLOAD_ROUTE, request)
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(AdaptiveError::ChunkedUploadInitFailed {
+                status: response.status().to_string(),
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+
+        let init_response: InitChunkedUploadResponse = response.json().await?;
+        Ok(init_response.session_id)
+    }
+
+    async fn upload_part(
+        &self,
+        session_id: &str,
+        part_number: u64,
+        data: Vec<u8>,
+        progress_tx: mpsc::UnboundedSender<u64>,
+    ) -> Result<()> {
+        const SUB_CHUNK_SIZE: usize = 64 * 1024;
+
+        let url = self.rest_base_url.join(UPLOAD_PART_ROUTE)?;
+
+        let chunks: Vec<Vec<u8>> = data
+            .chunks(SUB_CHUNK_SIZE)
+            .map(|chunk| chunk.to_vec())
+            .collect();
+
+        let stream = futures::stream::iter(chunks).map(move |chunk| {
+            let len = chunk.len() as u64;
+            let _ = progress_tx.send(len);
+            Ok::<_, std::io::Error>(chunk)
+        });
+
+        let body = reqwest::Body::wrap_stream(stream);
+
+        let request = self
+            .client
+            .post(url)
+            .bearer_auth(&self.auth_token)
+            .timeout(UPLOAD_TIMEOUT)
+            .query(&[
+                ("session_id", session_id),
+                ("part_number", &part_number.to_string()),
+            ])
+            .header("Content-Type", "application/octet-stream")
+            .body(body);
+        let response = self.transport.send(UPLOAD_PART_ROUTE, request).await?;
+
+        if !response.status().is_success() {
+            return Err(AdaptiveError::ChunkedUploadPartFailed {
+                part_number,
+                status: response.status().to_string(),
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Abort a chunked upload session.
+    pub async fn abort_chunked_upload(&self, session_id: &str) -> Result<()> {
+        let url = self.rest_base_url.join(ABORT_CHUNKED_UPLOAD_ROUTE)?;
+
+        let request = AbortChunkedUploadRequest {
+            session_id: session_id.to_string(),
+        };
+
+        let request = self
+            .client
+            .delete(url)
+            .bearer_auth(&self.auth_token)
+            .timeout(self.request_timeout)
+            .json(&request);
+        let _ = self
+            .transport
+            .send(ABORT_CHUNKED_UPLOAD_ROUTE, request)
+            .await;
+
+        Ok(())
+    }
+
+    async fn create_dataset_from_multipart(
+        &self,
+        project: &str,
+        name: &str,
+        key: &str,
+        session_id: &str,
+    ) -> Result<
+        create_dataset_from_multipart::CreateDatasetFromMultipartCreateDatasetFromMultipartUpload,
+    > {
+        let variables = create_dataset_from_multipart::Variables {
+            input: create_dataset_from_multipart::DatasetCreateFromMultipartUpload {
+                project: project.to_string(),
+                name: name.to_string(),
+                key: Some(key.to_string()),
+                source: None,
+                upload_session_id: session_id.to_string(),
+            },
+        };
+
+        let request_body = CreateDatasetFromMultipart::build_query(variables);
+
+        // The server assembles the uploaded parts before answering, which for large datasets
+        // takes much longer than `request_timeout`
+        let request = self
+            .client
+            .post(self.graphql_url.clone())
+            .bearer_auth(&self.auth_token)
+            .timeout(UPLOAD_TIMEOUT)
+            .json(&request_body);
+
+        let response_data: create_dataset_from_multipart::ResponseData = self
+            .send_graphql(request_body.operation_name, request)
+            .await?;
+        Ok(response_data.create_dataset_from_multipart_upload)
+    }
+
+    /// Upload a dataset file in parts, skipping the parts of the `resume` session that are
+    /// already uploaded. Set `abort_on_failure` when a failed upload won't be resumed.
+    #[allow(clippy::too_many_arguments)]
+    pub fn chunked_upload_dataset<'a, P: AsRef<Path> + Send + 'a>(
+        &'a self,
+        project: &'a str,
+        name: &'a str,
+        key: &'a str,
+        dataset: P,
+        content_type: &'a str,
+        concurrency: usize,
+        resume: Option<UploadSession>,
+        abort_on_failure: bool,
+    ) -> Result<BoxStream<'a, Result<UploadEvent>>> {
+        let file_size = std::fs::metadata(dataset.as_ref())?.len();
+
+        let (total_parts, chunk_size) = calculate_upload_parts(file_size)?;
+
+        // A session for a different chunking can't be continued
+        let resume = resume.filter(|session| {
+            session.total_parts == total_parts && session.chunk_size == chunk_size
+        });
+        let skipped_parts = resume
+            .as_ref()
+            .map(|session| session.uploaded_parts.clone())
+            .unwrap_or_default();
+
+        let parts = async_stream::try_stream! {
+            let mut file = File::open(dataset.as_ref())?;
+
+            for part_number in 1..=total_parts {
+                if skipped_parts.contains(&part_number) {
+                    continue;
+                }
+                file.seek(SeekFrom::Start((part_number - 1) * chunk_size))?;
+                let mut buffer = Vec::with_capacity(chunk_size as usize);
+                (&mut file).take(chunk_size).read_to_end(&mut buffer)?;
+                yield (part_number, buffer);
+            }
+        };
+
+        Ok(self.upload_parts(
+            project,
+            name,
+            key,
+            file_size,
+            content_type,
+            (total_parts, chunk_size),
+            parts,
+            concurrency,
+            resume,
+            abort_on_failure,
+        ))
+    }
+
+    /// Upload a dataset streamed from an HTTP response, such as a download from another
+    /// server, without storing it locally. The response must have a known content length.
+    #[allow(clippy::too_many_arguments)]
+    pub fn chunked_upload_dataset_from_response<'a>(
+        &'a self,
+        project: &'a str,
+        name: &'a str,
+        key: &'a str,
+        response: reqwest::Response,
+        file_size: u64,
+        content_type: &'a str,
+        concurrency: usize,
+    ) -> Result<BoxStream<'a, Result<UploadEvent>>> {
+        let (total_parts, chunk_size) = calculate_upload_parts(file_size)?;
+        let chunk_size = chunk_size as usize;
+
+        let parts = async_stream::try_stream! {
+            let mut body = response.bytes_stream();
+            let mut buffer = Vec::with_capacity(chunk_size);
+
+            while let Some(bytes) = body.next().await {
+                let mut bytes = &bytes?[..];
+                while !bytes.is_empty() {
+                    let take = (chunk_size - buffer.len()).min(bytes.len());
+                    buffer.extend_from_slice(&bytes[..take]);
+                    bytes = &bytes[take..];
+                    if buffer.len() == chunk_size {
+                        yield std::mem::replace(&mut buffer, Vec::with_capacity(chunk_size));
+                    }
+                }
+            }
+
+            if !buffer.is_empty() {
+                yield buffer;
+            }
+        };
+        let parts = parts
+            .enumerate()
+            .map(|(i, chunk_data)| chunk_data.map(|data| (i as u64 + 1, data)));
+
+        Ok(self.upload_parts(
+            project,
+            name,
+            key,
+            file_size,
+            content_type,
+            (total_parts, chunk_size as u64),
+            parts,
+            concurrency,
+            None,
+            true,
+        ))
+    }
+
+    /// Upload the numbered parts of a chunked upload and create the dataset from them.
+    ///
+    /// Sources that can't be read again abort the session on failure. Otherwise the
+    /// session is kept on the server so that a later call can `resume` it and skip the
+    /// parts that were already uploaded.
+    #[allow(clippy::too_many_arguments)]
+    fn upload_parts<'a>(
+        &'a self,
+        project: &'a str,
+        name: &'a str,
+        key: &'a str,
+        file_size: u64,
+        content_type: &'a str,
+        (total_parts, chunk_size): (u64, u64),
+        parts: impl Stream<Item = Result<(u64, Vec<u8>)>> + Send + 'a,
+        concurrency: usize,
+        resume: Option<UploadSession>,
+        abort_on_failure: bool,
+    ) -> BoxStream<'a, Result<UploadEvent>> {
+        let stream = async_stream::try_stream! {
+            let mut session = match resume {
+                Some(session) => session,
+                None => UploadSession {
+                    session_id: self.init_chunked_upload(total_parts, content_type).await?,
+                    chunk_size,
+                    total_parts,
+                    uploaded_parts: BTreeSet::new(),
+                },
+            };
+            let session_id = session.session_id.clone();
+
+            let mut bytes_uploaded = session
+                .uploaded_parts
+                .iter()
+                .map(|part| chunk_size.min(file_size.saturating_sub((part - 1) * chunk_size)))
+                .sum::<u64>();
+            let mut uploaded_parts = session.uploaded_parts.len() as u64;
+
+            yield UploadEvent::Progress(ChunkedUploadProgress {
+                bytes_uploaded,
+                total_bytes: file_size,
+            });
+            yield UploadEvent::Session(session.clone());
+
+            let (progress_tx, mut progress_rx) = mpsc::unbounded_channel::<u64>();
+
+            let upload_result: Result<()> = {
+                // Parts are only read from the source once a slot frees up, so at most
+                // `concurrency` parts are held in memory at a time
+                let session_id = &session_id;
+                let part_uploads = parts
+                    .map(|part| {
+                        let progress_tx = progress_tx.clone();
+                        async move {
+                            let (part_number, chunk_data) = part?;
+                            if part_number > total_parts {
+                                return Err(AdaptiveError::SourceSizeMismatch { size: file_size });
+                            }
+                            self.upload_part(session_id, part_number, chunk_data, progress_tx)
+                                .await
+                                .map(|()| part_number)
+                        }
+                    })
+                    .buffer_unordered(concurrency.max(1));
+                tokio::pin!(part_uploads);
+
+                loop {
+                    tokio::select! {
+                        biased;
+                        Some(bytes) = progress_rx.recv() => {
+                            bytes_uploaded += bytes;
+                            yield UploadEvent::Progress(ChunkedUploadProgress {
+                                bytes_uploaded,
+                                total_bytes: file_size,
+                            });
+                        }
+                        result = part_uploads.next() => match result {
+                            Some(Ok(part_number)) => {
+                                uploaded_parts += 1;
+                                session.uploaded_parts.insert(part_number);
+                                yield UploadEvent::Session(session.clone());
+                            }
+                            Some(Err(e)) => break Err(e),
+                            None if uploaded_parts < total_parts => {
+                                break Err(AdaptiveError::SourceSizeMismatch { size: file_size });
+                            }
+                            None => break Ok(()),
+                        }
+                    }
+                }
+            };
+
+            // In-flight parts have been dropped with the stream above
+            if let Err(e) = upload_result {
+                if abort_on_failure {
+                    let _ = self.abort_chunked_upload(&session_id).await;
+                }
+                Err(e)?;
+            }
+
+            // All parts are already on the server at this point, so retry finalizing before
+            // throwing the whole upload away.
+            let mut attempt = 1;
+            let create_result = loop {
+                match self
+                    .create_dataset_from_multipart(project, name, key, &session_id)
+                    .await
+                {
+                    Err(e) if e.is_transient() && attempt < FINALIZE_MAX_ATTEMPTS => {
+                        tokio::time::sleep(FINALIZE_INITIAL_BACKOFF * 2u32.pow(attempt - 1)).await;
+                        attempt += 1;
+                    }
+                    result => break result,
+                }
+            };
+
+            match create_result {
+                Ok(response) => {
+                    yield UploadEvent::Complete(response);
+                }
+                Err(e) => {
+                    if abort_on_failure {
+                        let _ = self.abort_chunked_upload(&session_id).await;
+                    }
+                    Err(AdaptiveError::DatasetCreationFailed(e.to_string()))?;
+                }
+            }
+        };
+
+        Box::pin(stream)
+    }
+
+    /// Start downloading a file from a server other than the Adaptive API, without sending the
+    /// API credentials along.
+    pub async fn fetch_external(&self, url: Url) -> Result<reqwest::Response> {
+        let response = self.transport.send(DOWNLOAD, self.client.get(url)).await?;
+        Ok(response.error_for_status()?)
+    }
+
+    /// Download a file from the given URL and write it to the specified path.
+    /// The URL can be absolute or relative to the API base URL.
+    pub async fn download_file_to_path(&self, url: &str, dest_path: &Path) -> Result<()> {
+        let mut progress = self.download_file_with_progress(url, dest_path);
+        while let Some(event) = progress.next().await {
+            event?;
+        }
+        Ok(())
+    }
+
+    /// Like [`Self::download_file_to_path`], streaming the response to disk and reporting
+    /// progress after every chunk written.
+    pub fn download_file_with_progress<'a>(
+        &'a self,
+        url: &'a str,
+        dest_path: &'a Path,
+    ) -> BoxStream<'a, Result<DownloadProgress>> {
+        use tokio::io::AsyncWriteExt;
+
+        let stream = async_stream::try_stream! {
+            let full_url = if url.starts_with("http://") || url.starts_with("https://") {
+                Url::parse(url)?
+            } else {
+                self.rest_base_url.join(url)?
+            };
+
+            let request = self.client.get(full_url).bearer_auth(&self.auth_token);
+            let response = self.transport.send(DOWNLOAD, request).await?;
+
+            let response = response.error_for_status().map_err(AdaptiveError::HttpError)?;
+
+            let total_bytes = response.content_length();
+            let mut bytes_downloaded = 0;
+            let mut file = tokio::fs::File::create(dest_path).await?;
+            let mut stream = response.bytes_stream();
+
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk?;
+                file.write_all(&chunk).await?;
+                bytes_downloaded += chunk.len() as u64;
+                yield DownloadProgress {
+                    bytes_downloaded,
+                    total_bytes,
+                };
+            }
+
+            file.flush().await?;
+        };
+
+        Box::pin(stream)
+    }
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use futures::future::BoxFuture;
+use http_body_util::BodyExt;
+use reqwest::{RequestBuilder, Response};
+
+use super::{
+    ABORT_CHUNKED_UPLOAD_ROUTE, AdaptiveError, DOWNLOAD, INIT_CHUNKED_UPLOAD_ROUTE, Result,
+    UPLOAD_PART_ROUTE,
+};
+
+/// Sends the requests built by [`super::AdaptiveClient`].
+pub trait Transport: Send + Sync {
+    /// Send `request`. `name` identifies the call for transports that don't use the network,
+    /// it is the operation name for GraphQL requests and the route for REST requests.
+    fn send<'a>(
+        &'a self,
+        name: &'a str,
+        request: RequestBuilder,
+    ) -> BoxFuture<'a, Result<Response>>;
+}
+
+pub struct HttpTransport;
+
+impl Transport for HttpTransport {
+    fn send<'a>(
+        &'a self,
+        _name: &'a str,
+        request: RequestBuilder,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move { Ok(request.send().await?) })
+    }
+}
+
+/// Answers requests with canned responses from a directory. GraphQL responses are read from
+/// `<operation name>.json`, the chunked upload session from `init_chunked_upload.json` and
+/// downloaded files from `download`. Upload parts and aborts always succeed.
+pub struct MockTransport {
+    dir: PathBuf,
+}
+
+impl MockTransport {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+impl Transport for MockTransport {
+    fn send<'a>(
+        &'a self,
+        name: &'a str,
+        request: RequestBuilder,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            // Read the body like the network would, so that upload progress is reported
+            if let Some(body) = request.build()?.body_mut().take() {
+                body.collect().await?;
+            }
+
+            let file = match name {
+                UPLOAD_PART_ROUTE | ABORT_CHUNKED_UPLOAD_ROUTE => {
+                    return Ok(http::Response::new(Vec::new()).into());
+                }
+                INIT_CHUNKED_UPLOAD_ROUTE => "init_chunked_upload.json".to_string(),
+                DOWNLOAD => "download".to_string(),
+                operation_name => format!("{operation_name}.json"),
+            };
+            let path = self.dir.join(file);
+            let body = tokio::fs::read(&path)
+                .await
+                .map_err(|_| AdaptiveError::MockResponseMissing(path))?;
+
+            Ok(http::Response::new(body).into())
+        })
+    }
+}
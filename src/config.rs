@@ -1,64 +1,243 @@
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use dotenvy::dotenv;
 use keyring::Entry;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use url::Url;
 
 pub const KEYRING_SERVICE: &str = "adpt-api-key";
 pub const KEYRING_USER: &str = "Adaptive";
+const NO_KEYRING_ENV: &str = "ADPT_NO_KEYRING";
+const CONFIG_PATH_ENV: &str = "ADPT_CONFIG";
+const PROFILE_ENV: &str = "ADPT_PROFILE";
+
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+static MOCK_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+static PROFILE_OVERRIDE: OnceLock<String> = OnceLock::new();
 
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct ConfigFile {
+    pub default_profile: Option<String>,
+    #[serde(flatten)]
+    pub settings: ProfileConfig,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+/// Settings that can be given at the top level of the config file or per profile.
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct ProfileConfig {
     pub default_project: Option<String>,
     pub adaptive_base_url: Option<Url>,
+    pub adaptive_graphql_url: Option<Url>,
+    pub adaptive_rest_url: Option<Url>,
+    pub keyring_service: Option<String>,
+    pub keyring_user: Option<String>,
+    pub request_timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Default)]
 struct ConfigEnv {
     default_project: Option<String>,
     adaptive_base_url: Option<Url>,
+    adaptive_graphql_url: Option<Url>,
+    adaptive_rest_url: Option<Url>,
     adaptive_api_key: Option<String>,
+    adpt_mock_dir: Option<PathBuf>,
+    adpt_request_timeout_secs: Option<u64>,
+    adpt_connect_timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeySource {
+    Environment,
+    Keyring,
+    /// Mock mode never sends the key anywhere, so none is needed
+    None,
+}
+
+impl std::fmt::Display for ApiKeySource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKeySource::Environment => write!(f, "ADAPTIVE_API_KEY environment variable"),
+            ApiKeySource::Keyring => write!(f, "OS keyring"),
+            ApiKeySource::None => write!(f, "none"),
+        }
+    }
 }
 
 pub struct Config {
+    pub profile: Option<String>,
     pub default_project: Option<String>,
     pub adaptive_base_url: Url,
+    pub adaptive_graphql_url: Option<Url>,
+    pub adaptive_rest_url: Option<Url>,
     pub adaptive_api_key: String,
+    pub api_key_source: ApiKeySource,
+    pub mock_dir: Option<PathBuf>,
+    pub request_timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+}
+
+impl ConfigFile {
+    /// The settings for a profile, falling back to the top level ones for anything the
+    /// profile leaves unset. Each profile gets its own keyring entry unless it names one.
+    pub fn resolve(&self, profile: Option<&str>) -> ProfileConfig {
+        let Some(name) = profile else {
+            return self.settings.clone();
+        };
+        let profile = self.profiles.get(name).cloned().unwrap_or_default();
+        let base = &self.settings;
+
+        ProfileConfig {
+            default_project: profile.default_project.or(base.default_project.clone()),
+            adaptive_base_url: profile.adaptive_base_url.or(base.adaptive_base_url.clone()),
+            adaptive_graphql_url: profile
+                .adaptive_graphql_url
+                .or(base.adaptive_graphql_url.clone()),
+            adaptive_rest_url: profile.adaptive_rest_url.or(base.adaptive_rest_url.clone()),
+            keyring_service: profile.keyring_service.or(base.keyring_service.clone()),
+            keyring_user: Some(profile.keyring_user.unwrap_or_else(|| {
+                let user = base.keyring_user.as_deref().unwrap_or(KEYRING_USER);
+                format!("{user}-{name}")
+            })),
+            request_timeout_secs: profile.request_timeout_secs.or(base.request_timeout_secs),
+            connect_timeout_secs: profile.connect_timeout_secs.or(base.connect_timeout_secs),
+        }
+    }
+
+    /// The settings `adpt config` should edit for the selected profile.
+    pub fn settings_mut(&mut self, profile: Option<&str>) -> &mut ProfileConfig {
+        match profile {
+            Some(name) => self.profiles.entry(name.to_string()).or_default(),
+            None => &mut self.settings,
+        }
+    }
 }
 
-fn merge_config(base: ConfigFile, override_config: ConfigEnv) -> Result<Config> {
-    let default_project = override_config.default_project.or(base.default_project);
+fn merge_config(
+    profile: Option<String>,
+    base: ProfileConfig,
+    override_config: ConfigEnv,
+    no_keyring: bool,
+) -> Result<Config> {
+    let default_project = override_config
+        .default_project
+        .or(base.default_project.clone());
+
+    let mock_dir = MOCK_DIR_OVERRIDE
+        .get()
+        .cloned()
+        .or(override_config.adpt_mock_dir);
 
+    // A mock client never reaches a server, so it doesn't need a real one configured
     let mut adaptive_base_url = override_config
         .adaptive_base_url
-        .or(base.adaptive_base_url)
+        .or(base.adaptive_base_url.clone())
+        .or_else(|| {
+            mock_dir
+                .as_ref()
+                .map(|_| Url::parse("http://localhost/").expect("Invalid mock URL"))
+        })
         .ok_or(anyhow!("No adaptive base URL provided"))?;
 
     adaptive_base_url = adaptive_base_url
         .join("/api/")
         .context("Failed to append /api to base URL")?;
 
-    let adaptive_api_key = if let Some(api_key) = override_config.adaptive_api_key {
-        api_key
+    let adaptive_graphql_url = override_config
+        .adaptive_graphql_url
+        .or(base.adaptive_graphql_url.clone());
+
+    // REST endpoints are joined onto this URL, so it must be treated as a directory
+    let adaptive_rest_url = override_config
+        .adaptive_rest_url
+        .or(base.adaptive_rest_url.clone())
+        .map(|mut url| {
+            if !url.path().ends_with('/') {
+                url.set_path(&format!("{}/", url.path()));
+            }
+            url
+        });
+
+    let (adaptive_api_key, api_key_source) = if let Some(api_key) = override_config.adaptive_api_key
+    {
+        (api_key, ApiKeySource::Environment)
+    } else if mock_dir.is_some() {
+        (String::new(), ApiKeySource::None)
+    } else if no_keyring {
+        bail!(
+            "API key not specified via the ADAPTIVE_API_KEY environment variable.\n\
+            The OS keyring is disabled, so the key must be provided via the environment or a .env file."
+        );
     } else {
-        let entry = Entry::new(KEYRING_SERVICE, KEYRING_USER)?;
+        let entry = keyring_entry(&base)?;
         let api_key = entry.get_secret().context(
             "API key not specified via environment variable nor present in OS keyring.\n\
             Use `adpt set-api-key <your-key>` to set it.",
         )?;
-        String::from_utf8(api_key)?
+        (String::from_utf8(api_key)?, ApiKeySource::Keyring)
     };
 
     Ok(Config {
+        profile,
         default_project,
         adaptive_base_url,
+        adaptive_graphql_url,
+        adaptive_rest_url,
         adaptive_api_key,
+        api_key_source,
+        mock_dir,
+        request_timeout_secs: override_config
+            .adpt_request_timeout_secs
+            .or(base.request_timeout_secs),
+        connect_timeout_secs: override_config
+            .adpt_connect_timeout_secs
+            .or(base.connect_timeout_secs),
     })
 }
 
-fn get_config_file_path() -> Result<PathBuf> {
+pub fn set_config_file_path(path: PathBuf) {
+    let _ = CONFIG_PATH_OVERRIDE.set(path);
+}
+
+pub fn set_mock_dir(path: PathBuf) {
+    let _ = MOCK_DIR_OVERRIDE.set(path);
+}
+
+pub fn set_profile(profile: String) {
+    let _ = PROFILE_OVERRIDE.set(profile);
+}
+
+/// The profile chosen via `--profile`, ADPT_PROFILE or `default_profile`, if any.
+pub fn selected_profile(config: &ConfigFile) -> Option<String> {
+    if let Some(profile) = PROFILE_OVERRIDE.get() {
+        return Some(profile.clone());
+    }
+
+    let _ = dotenv();
+    std::env::var(PROFILE_ENV)
+        .ok()
+        .filter(|profile| !profile.is_empty())
+        .or(config.default_profile.clone())
+}
+
+pub fn get_config_file_path() -> Result<PathBuf> {
+    if let Some(path) = CONFIG_PATH_OVERRIDE.get() {
+        return Ok(path.clone());
+    }
+
+    let _ = dotenv();
+    if let Ok(path) = std::env::var(CONFIG_PATH_ENV)
+        && !path.is_empty()
+    {
+        return Ok(PathBuf::from(path));
+    }
+
     #[cfg(target_os = "macos")]
     {
         let base_dirs =
@@ -74,22 +253,60 @@ fn get_config_file_path() -> Result<PathBuf> {
     }
 }
 
-pub fn read_config() -> Result<Config> {
+pub fn keyring_disabled(no_keyring: bool) -> bool {
     let _ = dotenv();
-    let env_config = envy::from_env::<ConfigEnv>().unwrap_or_default();
+    no_keyring
+        || std::env::var(NO_KEYRING_ENV)
+            .is_ok_and(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes"))
+}
+
+fn keyring_entry(config: &ProfileConfig) -> Result<Entry> {
+    let service = config.keyring_service.as_deref().unwrap_or(KEYRING_SERVICE);
+    let user = config.keyring_user.as_deref().unwrap_or(KEYRING_USER);
+    Ok(Entry::new(service, user)?)
+}
 
+pub fn read_config_file() -> Result<ConfigFile> {
     let config_file = get_config_file_path()?;
-    let file_config = if let Ok(config) = fs::read_to_string(config_file) {
-        toml::from_str(&config)?
+    if let Ok(config) = fs::read_to_string(config_file) {
+        Ok(toml::from_str(&config)?)
     } else {
-        ConfigFile::default()
-    };
+        Ok(ConfigFile::default())
+    }
+}
+
+pub fn read_config(no_keyring: bool) -> Result<Config> {
+    let no_keyring = keyring_disabled(no_keyring);
+    let env_config = envy::from_env::<ConfigEnv>().unwrap_or_default();
+
+    let config_file = read_config_file()?;
+    let profile = selected_profile(&config_file);
+    if let Some(profile) = &profile
+        && !config_file.profiles.contains_key(profile)
+    {
+        bail!("Profile '{profile}' not found in the config file");
+    }
+
+    let settings = config_file.resolve(profile.as_deref());
+    merge_config(profile, settings, env_config, no_keyring)
+}
+
+/// The default project, which unlike [`read_config`] needs neither an API key nor a base URL.
+pub fn read_default_project() -> Result<Option<String>> {
+    let _ = dotenv();
+    let env_config = envy::from_env::<ConfigEnv>().unwrap_or_default();
+    let config_file = read_config_file()?;
+    let profile = selected_profile(&config_file);
 
-    merge_config(file_config, env_config)
+    Ok(env_config
+        .default_project
+        .or(config_file.resolve(profile.as_deref()).default_project))
 }
 
 pub fn set_api_key_keyring(api_key: String) -> Result<()> {
-    let entry = Entry::new(KEYRING_SERVICE, KEYRING_USER)?;
+    let config_file = read_config_file()?;
+    let profile = selected_profile(&config_file);
+    let entry = keyring_entry(&config_file.resolve(profile.as_deref()))?;
     entry.set_secret(api_key.as_bytes())?;
     println!("API key set for use with adpt");
     Ok(())
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use serde::Deserialize;
+use serde_json::{Map, Value};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct JsonSchema {
@@ -8,6 +9,36 @@ pub struct JsonSchema {
     pub required: Vec<String>,
 }
 
+impl JsonSchema {
+    /// Check parameters against the schema, returning every problem found. Union
+    /// properties are left for the server to check.
+    pub fn validate(&self, parameters: &Map<String, Value>) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let mut required = self.required.iter().collect::<Vec<_>>();
+        required.sort();
+        for name in required {
+            if !parameters.contains_key(name) {
+                problems.push(format!("{name}: required parameter is missing"));
+            }
+        }
+
+        for (name, value) in parameters {
+            match self.properties.get(name) {
+                None => problems.push(format!("{name}: unknown parameter")),
+                Some(JsonSchemaPropertyContents::Regular(property)) => {
+                    if let Err(problem) = property.check(value) {
+                        problems.push(format!("{name}: {problem}"));
+                    }
+                }
+                Some(JsonSchemaPropertyContents::Union(_)) => {}
+            }
+        }
+
+        problems
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
 pub enum JsonSchemaPropertyContents {
@@ -28,4 +59,73 @@ pub struct RegularJsonSchemaPropertyContents {
     #[serde(rename = "type")]
     pub type_: String,
     pub description: String,
+    pub format: Option<String>,
+    pub items: Option<JsonSchemaItems>,
+    #[serde(rename = "enum")]
+    pub enum_: Option<Vec<Value>>,
+}
+
+impl RegularJsonSchemaPropertyContents {
+    /// The values a string property is restricted to, if the schema declares an `enum`.
+    pub fn allowed_values(&self) -> Option<Vec<String>> {
+        let values = self.enum_.as_ref()?;
+        Some(
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_owned))
+                .collect(),
+        )
+    }
+
+    fn check(&self, value: &Value) -> Result<(), String> {
+        if self.type_ == "array" {
+            let Value::Array(values) = value else {
+                return Err(format!("expected an array, got {value}"));
+            };
+            if let Some(items) = &self.items {
+                for value in values {
+                    check_type(&items.type_, value)?;
+                }
+            }
+            return Ok(());
+        }
+
+        check_type(&self.type_, value)?;
+        if let Some(allowed) = &self.enum_
+            && !allowed.contains(value)
+        {
+            let allowed = allowed
+                .iter()
+                .map(Value::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(format!("{value} is not one of {allowed}"));
+        }
+        Ok(())
+    }
+}
+
+fn check_type(type_: &str, value: &Value) -> Result<(), String> {
+    let matches = match type_ {
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        // Types this tool doesn't know about are left for the server to check
+        _ => true,
+    };
+
+    if matches {
+        Ok(())
+    } else {
+        Err(format!("expected {type_}, got {value}"))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JsonSchemaItems {
+    #[serde(rename = "type")]
+    pub type_: String,
 }
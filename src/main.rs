@@ -1,18 +1,24 @@
-use adaptive_client_rust::{AdaptiveClient, UploadEvent, create_user};
 use anyhow::{Context, Result, anyhow, bail};
 use autumnus::{FormatterOption, Options, highlight, themes};
 use clap::{
-    Arg, Args, Command, CommandFactory, Parser, Subcommand, ValueEnum, ValueHint, value_parser,
+    Arg, ArgAction, ArgGroup, Args, Command, CommandFactory, Parser, Subcommand, ValueEnum,
+    ValueHint, builder::PossibleValuesParser, value_parser,
 };
 use clap_complete::{ArgValueCompleter, CompletionCandidate};
 use email_address::EmailAddress;
-use futures::StreamExt;
+use futures::{
+    StreamExt,
+    future::{join_all, try_join_all},
+    stream::BoxStream,
+};
 use iocraft::prelude::*;
+use serde::Serialize;
 use serde_json::{Map, Value};
 use slug::slugify;
 use std::{
+    collections::{BTreeMap, BTreeSet},
     fs,
-    io::{self, IsTerminal, Write},
+    io::{self, IsTerminal, Read, Write},
     path::{Path, PathBuf},
     sync::Arc,
     time::SystemTime,
@@ -23,23 +29,31 @@ use url::Url;
 use uuid::Uuid;
 use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
 
-use zip_extensions::{
-    zip_ignore_entry_handler::ZipIgnoreEntryHandler, zip_writer_extensions::ZipWriterExtensions,
-};
+use zip_extensions::zip_writer_extensions::ZipWriterExtensions;
 
 use crate::{
-    json_schema::{JsonSchema, JsonSchemaPropertyContents},
+    client::{
+        AdaptiveClient, DownloadProgress, JobFilter, UploadEvent, create_user,
+        get_custom_recipes::GetCustomRecipesCustomRecipes, get_job, list_jobs,
+    },
+    json_schema::{JsonSchema, JsonSchemaPropertyContents, RegularJsonSchemaPropertyContents},
+    pyproject::{PyProject, PyProjectEntryHandler},
     terminal::TitleGuard,
     ui::{
-        AllModelsList, Cell, Column, ConfigHeader, ErrorMessage, InputPrompt, JobsList, ListConfig,
-        ModelsList, ProgressBar, RecipeList, SuccessMessage, render_list,
+        AllModelsList, AllowedValues, Cell, Column, ConfigHeader, DatasetList, ErrorMessage,
+        InputPrompt, JobsList, ListConfig, ModelDisplay, ModelsList, ProgressBar, RecipeList,
+        Spinner, SuccessMessage, render_list,
     },
+    upload_state::UploadState,
 };
 
+mod client;
 mod config;
 mod json_schema;
+mod pyproject;
 mod terminal;
 mod ui;
+mod upload_state;
 
 const DEFAULT_ADAPTIVE_BASE_URL: &str = "https://app.adaptive.ml";
 
@@ -52,29 +66,177 @@ struct Cli {
     command: Commands,
     #[arg(long, hide = true)]
     markdown_help: bool,
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Pretty)]
+    output: OutputFormat,
+    /// Never access the OS keyring, the API key must be provided via ADAPTIVE_API_KEY.
+    /// Can also be enabled by setting ADPT_NO_KEYRING=1
+    #[arg(long, global = true)]
+    no_keyring: bool,
+    /// Use this config file instead of the default location.
+    /// Can also be set via ADPT_CONFIG
+    #[arg(long, global = true, value_hint = ValueHint::FilePath)]
+    config: Option<PathBuf>,
+    /// Use the settings of this profile from the config file.
+    /// Can also be set via ADPT_PROFILE
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// Read canned API responses from this directory instead of contacting the server.
+    /// Can also be set via ADPT_MOCK_DIR
+    #[arg(long, global = true, value_hint = ValueHint::DirPath)]
+    mock_dir: Option<PathBuf>,
+    /// Skip checking that the API is reachable and the API key valid before uploads,
+    /// publishing and waiting for runs
+    #[arg(long, global = true)]
+    no_preflight: bool,
+    /// Disable colored output. Also disabled when the NO_COLOR environment variable is set
+    #[arg(long, global = true)]
+    no_color: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human readable output
+    Pretty,
+    /// Machine readable JSON output
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SchemaFormat {
+    /// Syntax highlighted JSON
+    Pretty,
+    /// Plain JSON
+    Json,
+    /// YAML
+    Yaml,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum JobKindFilter {
+    /// Jobs of any kind
+    All,
+    /// Recipe runs
+    Custom,
+    /// Interactive sessions
+    Interactive,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum JobStatusFilter {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+    Canceled,
+}
+
+impl JobStatusFilter {
+    fn status(self) -> list_jobs::JobStatus {
+        match self {
+            JobStatusFilter::Pending => list_jobs::JobStatus::PENDING,
+            JobStatusFilter::Running => list_jobs::JobStatus::RUNNING,
+            JobStatusFilter::Completed => list_jobs::JobStatus::COMPLETED,
+            JobStatusFilter::Failed => list_jobs::JobStatus::FAILED,
+            JobStatusFilter::Canceled => list_jobs::JobStatus::CANCELED,
+        }
+    }
+}
+
+/// The statuses to list jobs with, only running and pending jobs unless asked otherwise.
+fn job_statuses(status: &[JobStatusFilter], all: bool) -> Option<Vec<list_jobs::JobStatus>> {
+    if all {
+        None
+    } else if status.is_empty() {
+        Some(vec![
+            list_jobs::JobStatus::RUNNING,
+            list_jobs::JobStatus::PENDING,
+        ])
+    } else {
+        Some(status.iter().map(|status| status.status()).collect())
+    }
+}
+
+impl JobKindFilter {
+    fn kinds(self) -> Option<Vec<list_jobs::JobKind>> {
+        match self {
+            JobKindFilter::All => None,
+            JobKindFilter::Custom => Some(vec![list_jobs::JobKind::CUSTOM]),
+            JobKindFilter::Interactive => Some(vec![list_jobs::JobKind::INTERACTIVE]),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum JobsGroupBy {
+    /// Group by job status
+    Status,
+    /// Group by the user who created the job
+    User,
+    /// Group by the recipe the job runs
+    Recipe,
 }
 
 #[derive(Args)]
+#[command(group(ArgGroup::new("waiting").args(["wait", "follow"]).multiple(true)))]
 struct RunArgs {
     /// Recipe ID or key
     #[arg(add = ArgValueCompleter::new(recipe_key_completer))]
     recipe: String,
-    /// A file containing a JSON object of parameters for the recipe
+    /// A file containing a JSON object of parameters for the recipe, or `-` to read it from stdin.
+    /// Can be repeated, later files are deep-merged over earlier ones and recipe arguments
+    /// given after `--` are applied last
     #[arg(long, value_hint = ValueHint::FilePath)]
-    parameters: Option<PathBuf>,
+    parameters: Vec<PathBuf>,
     /// The name of the run
     #[arg(short, long)]
     name: Option<String>,
     /// The compute pool to run the recipe on
     #[arg(short, long, add = ArgValueCompleter::new(pool_completer))]
     compute_pool: Option<String>,
-    /// The number of GPUs to run the recipe on
+    /// The number of GPUs to run the recipe on, 0 for CPU-only recipes. Whether the compute pool
+    /// allows CPU-only runs is checked by the server
+    #[arg(short, long, default_value_t = 1)]
+    gpus: u32,
+    /// Wait for the job to finish
+    #[arg(long)]
+    wait: bool,
+    /// Follow the job's progress until it finishes, exiting with an error if it fails or is canceled
     #[arg(short, long)]
-    gpus: Option<u32>,
+    follow: bool,
+    /// Download the job's artifacts to this directory once it completes, or save its status if it
+    /// fails
+    #[arg(long, requires = "waiting", value_hint = ValueHint::DirPath)]
+    output_dir: Option<PathBuf>,
     #[arg(last = true, num_args = 1..)]
     args: Vec<String>,
 }
 
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print the location of the config file and whether it exists. When run in a pipe only the
+    /// path is printed and the command fails if the file does not exist
+    Path,
+}
+
+#[derive(Subcommand)]
+enum RecipeCommands {
+    /// Compare a published recipe with a local copy
+    Diff {
+        #[arg(short, long, add = ArgValueCompleter::new(project_completer))]
+        project: Option<String>,
+        /// Recipe ID or key
+        #[arg(add = ArgValueCompleter::new(recipe_key_completer))]
+        recipe: String,
+        /// Local recipe directory or file
+        #[arg(value_hint = ValueHint::AnyPath)]
+        path: PathBuf,
+        /// Show line differences of modified text files
+        #[arg(short, long)]
+        lines: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum RoleCommands {
     /// Create a new role
@@ -187,10 +349,26 @@ enum TeamCommands {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Cancel a job
-    Cancel { id: Uuid },
+    /// Cancel jobs
+    Cancel {
+        /// IDs of the jobs to cancel
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        ids: Vec<Uuid>,
+        /// Cancel all running and pending jobs
+        #[arg(long)]
+        all: bool,
+        /// Only cancel jobs of this project when using --all
+        #[arg(short, long, requires = "all", add = ArgValueCompleter::new(project_completer))]
+        project: Option<String>,
+        /// Cancel all jobs without asking for confirmation
+        #[arg(short, long, requires = "all")]
+        yes: bool,
+    },
     /// Configure adpt interactively
-    Config,
+    Config {
+        #[command(subcommand)]
+        command: Option<ConfigCommands>,
+    },
     /// Inspect job
     Job {
         id: Uuid,
@@ -198,8 +376,37 @@ enum Commands {
         #[arg(short, long)]
         follow: bool,
     },
-    /// List currently running jobs
-    Jobs,
+    /// List jobs, by default those currently running or pending
+    Jobs {
+        /// Only include jobs of this project
+        #[arg(short, long, add = ArgValueCompleter::new(project_completer))]
+        project: Option<String>,
+        /// Write a snapshot of job counts and running job progress to a file instead.
+        /// Files ending in `.prom` are written in the Prometheus text format, otherwise JSON
+        #[arg(long, value_hint = ValueHint::FilePath)]
+        export: Option<PathBuf>,
+        /// Only include jobs newer than the job with this ID
+        #[arg(long)]
+        since_id: Option<Uuid>,
+        /// Only include jobs of this kind
+        #[arg(long, value_enum, default_value_t = JobKindFilter::All)]
+        kind: JobKindFilter,
+        /// Only include jobs with this status, can be repeated. Defaults to running and pending jobs
+        #[arg(long, value_enum)]
+        status: Vec<JobStatusFilter>,
+        /// Include jobs of any status
+        #[arg(long, conflicts_with = "status")]
+        all: bool,
+        /// Only include jobs created within this time, for example `2h` or `7days`
+        #[arg(long, value_parser = humantime::parse_duration)]
+        since: Option<std::time::Duration>,
+        /// Show at most this many jobs, 0 to show all of them
+        #[arg(long, default_value_t = DEFAULT_JOBS_LIMIT, conflicts_with = "export")]
+        limit: usize,
+        /// Show jobs in separate groups
+        #[arg(long, value_enum, conflicts_with = "export")]
+        group_by: Option<JobsGroupBy>,
+    },
     /// List models
     Models {
         #[arg(short, long, add = ArgValueCompleter::new(project_completer))]
@@ -212,11 +419,37 @@ enum Commands {
     Upload {
         #[arg(short, long, add = ArgValueCompleter::new(project_completer))]
         project: Option<String>,
+        /// Dataset file, an HTTP(S) URL to stream it from, or `-` to read it from stdin
         #[arg(value_hint = ValueHint::AnyPath)]
         dataset: PathBuf,
-        /// Dataset name
+        /// Dataset name, required when reading from stdin
         #[arg(short, long)]
         name: Option<String>,
+        /// Number of parts of a large dataset to upload at the same time
+        #[arg(long, default_value_t = client::DEFAULT_UPLOAD_CONCURRENCY)]
+        concurrency: usize,
+        /// Start a new upload instead of resuming an interrupted one of the same file
+        #[arg(long)]
+        restart: bool,
+        /// Content type of the dataset, detected from the file extension by default
+        #[arg(long)]
+        content_type: Option<String>,
+    },
+    /// List datasets
+    Datasets {
+        #[arg(short, long, add = ArgValueCompleter::new(project_completer))]
+        project: Option<String>,
+    },
+    /// Download dataset
+    DownloadDataset {
+        #[arg(short, long, add = ArgValueCompleter::new(project_completer))]
+        project: Option<String>,
+        /// Dataset ID or key
+        #[arg(add = ArgValueCompleter::new(dataset_key_completer))]
+        dataset: String,
+        /// Where to write the dataset, defaults to the dataset's name in the current directory
+        #[arg(value_hint = ValueHint::FilePath)]
+        path: Option<PathBuf>,
     },
     /// Upload recipe
     Publish {
@@ -233,11 +466,17 @@ enum Commands {
         /// Update existing recipe if it exists
         #[arg(short, long)]
         force: bool,
+        /// List the files that would be published without publishing them
+        #[arg(long)]
+        dry_run: bool,
     },
     /// List recipes
     Recipes {
         #[arg(short, long, add = ArgValueCompleter::new(project_completer))]
         project: Option<String>,
+        /// Show a summary of the required parameters of each recipe
+        #[arg(long)]
+        show_params: bool,
     },
     /// Run recipe
     Run {
@@ -252,9 +491,22 @@ enum Commands {
         project: Option<String>,
         #[arg(add = ArgValueCompleter::new(recipe_key_completer))]
         recipe: String,
+        /// Output format, `pretty` on a terminal and `json` otherwise by default
+        #[arg(short, long)]
+        format: Option<SchemaFormat>,
     },
+    /// Show a summary of job counts and the progress of running jobs
+    Status,
+    /// Show the configuration in use and the user the API key belongs to, failing when the user
+    /// can't be fetched
+    Whoami,
     /// Store your API key in the OS keyring
     SetApiKey { api_key: String },
+    /// Manage recipes
+    Recipe {
+        #[command(subcommand)]
+        command: RecipeCommands,
+    },
     /// Manage roles
     Role {
         #[command(subcommand)]
@@ -270,25 +522,38 @@ enum Commands {
         #[command(subcommand)]
         command: TeamCommands,
     },
+    /// Generate man pages for adpt and its subcommands
+    #[command(hide = true)]
+    GenerateMan {
+        /// Directory to write the man pages to
+        #[arg(value_hint = ValueHint::DirPath)]
+        dir: PathBuf,
+    },
 }
 
 impl Commands {
     fn name(&self) -> &'static str {
         match self {
             Commands::Cancel { .. } => "cancel",
-            Commands::Config => "config",
+            Commands::Config { .. } => "config",
             Commands::Job { .. } => "job",
-            Commands::Jobs => "jobs",
+            Commands::Jobs { .. } => "jobs",
             Commands::Models { .. } => "models",
             Commands::Upload { .. } => "upload",
+            Commands::Datasets { .. } => "datasets",
+            Commands::DownloadDataset { .. } => "download-dataset",
             Commands::Publish { .. } => "publish",
             Commands::Recipes { .. } => "recipes",
             Commands::Run { .. } => "run",
             Commands::Schema { .. } => "schema",
+            Commands::Status => "status",
+            Commands::Whoami => "whoami",
             Commands::SetApiKey { .. } => "set-api-key",
+            Commands::Recipe { .. } => "recipe",
             Commands::Role { .. } => "role",
             Commands::User { .. } => "user",
             Commands::Team { .. } => "team",
+            Commands::GenerateMan { .. } => "generate-man",
         }
     }
 }
@@ -306,25 +571,77 @@ fn main() -> Result<()> {
         return Ok(());
     }
     let _title_guard = TitleGuard::new(&format!("adpt - {}", cli.command.name()));
+    let output = cli.output;
+    let preflight = !cli.no_preflight;
+    let no_keyring = config::keyring_disabled(cli.no_keyring);
+    if let Some(config_path) = cli.config {
+        config::set_config_file_path(config_path);
+    }
+    if let Some(profile) = cli.profile {
+        config::set_profile(profile);
+    }
+    if let Some(mock_dir) = cli.mock_dir {
+        config::set_mock_dir(mock_dir);
+    }
+    if cli.no_color {
+        ui::disable_color();
+    }
 
     rt.block_on(async {
         match cli.command {
-            Commands::Config => interactive_config(),
+            Commands::Config { command: None } => interactive_config(no_keyring),
+            Commands::Config {
+                command: Some(ConfigCommands::Path),
+            } => print_config_path(),
+            Commands::GenerateMan { dir } => generate_man_pages(&dir),
+            Commands::SetApiKey { .. } if no_keyring => bail!(
+                "The OS keyring is disabled via --no-keyring or ADPT_NO_KEYRING.\n\
+                Provide the API key via the ADAPTIVE_API_KEY environment variable or a .env file instead."
+            ),
             Commands::SetApiKey { api_key } => config::set_api_key_keyring(api_key),
+            // Only looks at local files, so it works without an API key
+            Commands::Publish {
+                project,
+                recipe,
+                name,
+                key,
+                dry_run: true,
+                ..
+            } => {
+                let project = require_project(resolve_project(
+                    project,
+                    &recipe_source_dir(&recipe),
+                    key.as_deref(),
+                    config::read_default_project()?,
+                )?)?;
+                preview_recipe(&project, name, key, &recipe)
+            }
             requires_api_key => {
-                let config = config::read_config()?;
-                let client = AdaptiveClient::new(config.adaptive_base_url, config.adaptive_api_key);
+                let config = config::read_config(no_keyring)?;
+                let client = client_from_config(&config);
                 let default_project = config.default_project.clone();
 
-                let load_project = |maybe_project: Option<String>| {
-                    maybe_project.or(default_project.clone()).expect(
-                        "A project must be specified via the --project argument or a default project configured"
+                let current_dir = std::env::current_dir()?;
+
+                let find_project = |maybe_project: Option<String>,
+                                    pyproject_dir: &Path,
+                                    recipe_key: Option<&str>| {
+                    resolve_project(
+                        maybe_project,
+                        pyproject_dir,
+                        recipe_key,
+                        default_project.clone(),
                     )
                 };
+                let load_project = |maybe_project: Option<String>,
+                                    pyproject_dir: &Path,
+                                    recipe_key: Option<&str>| {
+                    require_project(find_project(maybe_project, pyproject_dir, recipe_key)?)
+                };
 
                 match requires_api_key {
-                    Commands::Recipes { project } => {
-                                        list_recipes(&client, &load_project(project)).await
+                    Commands::Recipes { project, show_params } => {
+                                        list_recipes(&client, &load_project(project, &current_dir, None)?, show_params, output).await
                                     }
                     Commands::Job { id, follow } => get_job(Arc::new(client), id, follow).await,
                     Commands::Publish {
@@ -333,28 +650,135 @@ fn main() -> Result<()> {
                                         name,
                                         key,
                                         force,
-                                    } => publish_recipe(&client, &load_project(project), name, key, recipe, force).await,
+                                        dry_run: _,
+                                    } => {
+                                        if preflight {
+                                            check_api(&client).await?;
+                                        }
+                                        let project = load_project(project, &recipe_source_dir(&recipe), key.as_deref())?;
+                                        publish_recipe(&client, &project, name, key, recipe, force).await
+                                    }
                     Commands::Run { project, args } => {
-                                        run_recipe(&client, &load_project(project), args).await
+                                        if preflight && (args.wait || args.follow) {
+                                            check_api(&client).await?;
+                                        }
+                                        let project = load_project(project, &current_dir, Some(&args.recipe))?;
+                                        run_recipe(&client, &project, args).await
                                     }
-                    Commands::Jobs => list_jobs(&client, None).await,
-                    Commands::Cancel { id } => cancel_job(&client, id).await,
+                    Commands::Jobs {
+                        project,
+                        export,
+                        since_id,
+                        kind,
+                        status,
+                        all,
+                        since,
+                        limit,
+                        group_by,
+                    } => {
+                        let filter = JobFilter {
+                            project,
+                            kinds: kind.kinds(),
+                            statuses: job_statuses(&status, all),
+                            since_id,
+                            created_after: since.map(|since| SystemTime::now() - since),
+                        };
+                        match export {
+                            Some(path) => export_jobs(&client, &filter, &path).await,
+                            None => {
+                                let limit = (limit > 0).then_some(limit);
+                                list_jobs(&client, &filter, limit, group_by, output).await
+                            }
+                        }
+                    }
+                    Commands::Cancel {
+                        ids,
+                        all,
+                        project,
+                        yes,
+                    } => {
+                        if all {
+                            cancel_all_jobs(&client, project, yes, output).await
+                        } else {
+                            cancel_jobs(&client, ids, output).await
+                        }
+                    }
+                    Commands::Status => print_status(&client, output).await,
+                    Commands::Whoami => print_whoami(&client, &config, output).await,
                     Commands::Models { project, all } => {
                                         if all {
-                                            list_all_models(&client).await
+                                            list_all_models(&client, output).await
                                         } else {
-                                            match project.or(config.default_project) {
-                                                Some(project) => list_models(&client, project).await,
-                                                None => list_all_models(&client).await,
+                                            match find_project(project, &current_dir, None)? {
+                                                Some(project) => list_models(&client, project, output).await,
+                                                None => list_all_models(&client, output).await,
                                             }
                                         }
                                     }
-                    Commands::Schema { project, recipe } => {
-                                        print_schema(&client, load_project(project), recipe).await
-                                    }
-                    Commands::Config => panic!("This state should be unreachable"),
+                    Commands::Schema {
+                        project,
+                        recipe,
+                        format,
+                    } => {
+                        let project = load_project(project, &current_dir, Some(&recipe))?;
+                        let format = format.unwrap_or(
+                            if output == OutputFormat::Pretty && io::stdout().is_terminal() {
+                                SchemaFormat::Pretty
+                            } else {
+                                SchemaFormat::Json
+                            },
+                        );
+                        print_schema(&client, project, recipe, format).await
+                    }
+                    Commands::Config { .. } => panic!("This state should be unreachable"),
                     Commands::SetApiKey { api_key: _ } => panic!("This state should be unreachable"),
-                    Commands::Upload { project, dataset, name } => upload_dataset(&client, &load_project(project), dataset, name).await,
+                    Commands::GenerateMan { .. } => panic!("This state should be unreachable"),
+                    Commands::Upload {
+                        project,
+                        dataset,
+                        name,
+                        concurrency,
+                        restart,
+                        content_type,
+                    } => {
+                        if preflight {
+                            check_api(&client).await?;
+                        }
+                        let project = load_project(project, &current_dir, None)?;
+                        upload_dataset(
+                            &client,
+                            &project,
+                            dataset,
+                            name,
+                            content_type,
+                            concurrency,
+                            restart,
+                        )
+                        .await
+                    }
+                    Commands::Datasets { project } => {
+                        let project = load_project(project, &current_dir, None)?;
+                        list_datasets(&client, &project, output).await
+                    }
+                    Commands::DownloadDataset {
+                        project,
+                        dataset,
+                        path,
+                    } => {
+                        let project = load_project(project, &current_dir, None)?;
+                        download_dataset(&client, &project, &dataset, path).await
+                    }
+                    Commands::Recipe { command } => match command {
+                        RecipeCommands::Diff {
+                            project,
+                            recipe,
+                            path,
+                            lines,
+                        } => {
+                            let project = load_project(project, &recipe_source_dir(&path), Some(&recipe))?;
+                            diff_recipe(&client, &project, &recipe, &path, lines).await
+                        }
+                    },
                     Commands::Role { command } => match command {
                         RoleCommands::Create { name, key, permissions } => {
                             create_role(&client, &name, key.as_deref(), permissions).await
@@ -400,126 +824,660 @@ fn main() -> Result<()> {
     })
 }
 
-async fn upload_dataset<P: AsRef<Path> + Sync>(
-    client: &AdaptiveClient,
-    project: &str,
-    dataset: P,
-    name: Option<String>,
-) -> std::result::Result<(), anyhow::Error> {
-    let file_size = std::fs::metadata(dataset.as_ref())
-        .context("Failed to get file metadata")?
-        .len();
+/// Whether a chunked upload continues an interrupted upload of the same file.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Resume {
+    Continue,
+    Restart,
+    /// The file is temporary, so an interrupted upload can't be continued
+    Never,
+}
 
-    let name = name.unwrap_or_else(|| {
-        let file_name = dataset.as_ref().file_name().unwrap().to_string_lossy();
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .expect("SystemTime before UNIX EPOCH");
-        format!("{}-{}", file_name, now.as_secs())
-    });
+struct UploadedDataset {
+    id: Uuid,
+    key: Option<String>,
+}
+
+fn dataset_url(dataset: &Path) -> Result<Option<Url>> {
+    let Some(url) = dataset
+        .to_str()
+        .and_then(|dataset| Url::parse(dataset).ok())
+    else {
+        return Ok(None);
+    };
+
+    match url.scheme() {
+        "http" | "https" => Ok(Some(url)),
+        "s3" => bail!("S3 URLs are not supported, use a presigned HTTPS URL instead"),
+        _ => Ok(None),
+    }
+}
+
+fn dataset_file_name(dataset: &Path) -> String {
+    if let Ok(Some(url)) = dataset_url(dataset) {
+        url.path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|segment| !segment.is_empty())
+            .unwrap_or("dataset")
+            .to_string()
+    } else {
+        dataset.file_name().unwrap().to_string_lossy().to_string()
+    }
+}
+
+const PREFLIGHT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+async fn check_api(client: &AdaptiveClient) -> Result<()> {
+    let me = client.check_me(PREFLIGHT_TIMEOUT).await.context(
+        "Failed to reach the Adaptive API, check the base URL, API key and your connection",
+    )?;
+
+    if me.is_none() {
+        bail!("The API key is not valid for any user, set a new one with `adpt set-api-key`");
+    }
+
+    Ok(())
+}
+
+fn default_dataset_name(dataset: &Path) -> String {
+    let file_name = dataset_file_name(dataset);
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("SystemTime before UNIX EPOCH");
+    format!("{}-{}", file_name, now.as_secs())
+}
+
+async fn track_upload_progress(
+    mut stream: BoxStream<'_, std::result::Result<UploadEvent, client::AdaptiveError>>,
+) -> Result<Uuid> {
+    terminal::set_progress(terminal::Progress::SetPercentage(0));
+    let (tx, rx) = watch::channel(0.0);
+
+    let process_stream = async {
+        let mut response = None;
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(UploadEvent::Progress(p)) => {
+                    let percent = (p.bytes_uploaded as f32 / p.total_bytes as f32) * 100.0;
+                    let _ = tx.send(percent);
+                    terminal::set_progress(terminal::Progress::SetPercentage(percent as u8));
+                }
+                Ok(UploadEvent::Session(_)) => {}
+                Ok(UploadEvent::Complete(r)) => {
+                    response = Some(r);
+                    break;
+                }
+                Err(e) => {
+                    // The client has either aborted the upload session or left it to be resumed
+                    terminal::set_progress(terminal::Progress::SetError(*tx.borrow() as u8));
+                    return Err(anyhow!(e).context("Dataset upload failed"));
+                }
+            }
+        }
+        Ok::<_, anyhow::Error>(response.expect("Stream ended without Complete event"))
+    };
 
-    if file_size > adaptive_client_rust::MIN_CHUNK_SIZE_BYTES {
-        let key = slugify(&name);
-        let mut stream = client.chunked_upload_dataset(project, &name, &key, &dataset)?;
+    let mut progress_bar =
+        element!(ProgressBar(title: "Uploading Dataset".to_string(), progress: Some(rx)));
 
-        terminal::set_progress(terminal::Progress::SetPercentage(0));
-        let (tx, rx) = watch::channel(0.0);
+    let response = tokio::select! {
+        result = process_stream => result,
+        _ = progress_bar.render_loop() => {
+            unreachable!("render_loop should not terminate")
+        }
+    };
 
-        let process_stream = async {
-            let mut response = None;
-            while let Some(event) = stream.next().await {
-                match event? {
-                    UploadEvent::Progress(p) => {
-                        let percent = (p.bytes_uploaded as f32 / p.total_bytes as f32) * 100.0;
+    terminal::set_progress(terminal::Progress::None);
+    Ok(response?.dataset_id)
+}
+
+async fn track_download_progress(
+    mut stream: BoxStream<'_, std::result::Result<DownloadProgress, client::AdaptiveError>>,
+) -> Result<()> {
+    terminal::set_progress(terminal::Progress::SetPercentage(0));
+    let (tx, rx) = watch::channel(0.0);
+
+    let process_stream = async {
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(p) => {
+                    // Without a content length there is nothing to show progress against
+                    if let Some(total_bytes) = p.total_bytes.filter(|total| *total > 0) {
+                        let percent = (p.bytes_downloaded as f32 / total_bytes as f32) * 100.0;
                         let _ = tx.send(percent);
                         terminal::set_progress(terminal::Progress::SetPercentage(percent as u8));
                     }
-                    UploadEvent::Complete(r) => {
-                        response = Some(r);
-                        break;
-                    }
+                }
+                Err(e) => {
+                    terminal::set_progress(terminal::Progress::SetError(*tx.borrow() as u8));
+                    return Err(anyhow!(e).context("Dataset download failed"));
                 }
             }
-            Ok::<_, anyhow::Error>(response.expect("Stream ended without Complete event"))
-        };
+        }
+        Ok::<_, anyhow::Error>(())
+    };
 
-        let mut progress_bar =
-            element!(ProgressBar(title: "Uploading Dataset".to_string(), progress: Some(rx)));
+    let mut progress_bar =
+        element!(ProgressBar(title: "Downloading Dataset".to_string(), progress: Some(rx)));
+
+    let result = tokio::select! {
+        result = process_stream => result,
+        _ = progress_bar.render_loop() => {
+            unreachable!("render_loop should not terminate")
+        }
+    };
+
+    terminal::set_progress(terminal::Progress::None);
+    result
+}
+
+async fn with_spinner<F: Future>(title: &str, future: F) -> F::Output {
+    terminal::set_progress(terminal::Progress::SetIndeterminate);
+    let mut spinner = element! {
+        View {
+            Spinner()
+            Text(content: format!(" {}", title))
+        }
+    };
+
+    let output = tokio::select! {
+        output = future => output,
+        _ = spinner.render_loop() => {
+            unreachable!("render_loop should not terminate")
+        }
+    };
+
+    terminal::set_progress(terminal::Progress::None);
+    output
+}
 
-        let response = tokio::select! {
-            result = process_stream => result?,
-            _ = progress_bar.render_loop() => {
-                unreachable!("render_loop should not terminate")
+async fn upload_dataset_file<P: AsRef<Path> + Sync>(
+    client: &AdaptiveClient,
+    project: &str,
+    dataset: P,
+    name: &str,
+    content_type: Option<&str>,
+    concurrency: usize,
+    resume: Resume,
+) -> Result<UploadedDataset> {
+    if let Some(url) = dataset_url(dataset.as_ref())? {
+        return upload_dataset_url(client, project, url, name, content_type, concurrency).await;
+    }
+
+    let content_type =
+        content_type.unwrap_or_else(|| client::dataset_content_type(dataset.as_ref()));
+
+    let file_size = std::fs::metadata(dataset.as_ref())
+        .context("Failed to get file metadata")?
+        .len();
+
+    if file_size > client::MIN_CHUNK_SIZE_BYTES {
+        let key = slugify(name);
+        let state = match resume {
+            Resume::Never => None,
+            Resume::Continue | Resume::Restart => Some(UploadState::for_file(dataset.as_ref())?),
+        };
+        let mut session = state.as_ref().and_then(UploadState::load);
+        if resume == Resume::Restart
+            && let (Some(state), Some(session)) = (&state, session.take())
+        {
+            let _ = client.abort_chunked_upload(&session.session_id).await;
+            state.clear()?;
+        }
+
+        let stream = client
+            .chunked_upload_dataset(
+                project,
+                name,
+                &key,
+                &dataset,
+                content_type,
+                concurrency,
+                session,
+                // Without saved state the session can't be resumed, so don't leave it behind
+                state.is_none(),
+            )?
+            .inspect(|event| {
+                if let (Some(state), Ok(UploadEvent::Session(session))) = (&state, event) {
+                    let _ = state.save(session);
+                }
+            })
+            .boxed();
+        let id = match &state {
+            Some(state) => {
+                let id = track_upload_progress(stream).await.context(
+                    "Run the same command again to resume the upload, or pass --restart to start over",
+                )?;
+                state.clear()?;
+                id
             }
+            None => track_upload_progress(stream).await?,
         };
 
-        terminal::set_progress(terminal::Progress::None);
-        if io::stdout().is_terminal() {
-            println!(
-                "Dataset uploaded successfully with ID: {}",
-                response.dataset_id,
-            );
-        } else {
-            println!("{}", response.dataset_id);
-        }
-        terminal::send_notification("Dataset upload complete");
+        Ok(UploadedDataset { id, key: Some(key) })
     } else {
-        terminal::set_progress(terminal::Progress::SetIndeterminate);
-        let response = client.upload_dataset(project, &name, &dataset).await?;
+        let response = with_spinner(
+            "Uploading Dataset",
+            client.upload_dataset(project, name, &dataset, content_type),
+        )
+        .await?;
 
-        if io::stdout().is_terminal() {
-            println!(
-                "Dataset uploaded successfully with ID: {}, key: {}",
-                response.id,
-                response.key.unwrap_or("<none>".to_string())
-            );
-        } else {
-            println!("{}", response.id)
-        }
-        terminal::send_notification("Dataset upload complete");
+        Ok(UploadedDataset {
+            id: response.id,
+            key: response.key,
+        })
+    }
+}
+
+async fn upload_dataset_url(
+    client: &AdaptiveClient,
+    project: &str,
+    url: Url,
+    name: &str,
+    content_type: Option<&str>,
+    concurrency: usize,
+) -> Result<UploadedDataset> {
+    let file_name = dataset_file_name(Path::new(url.as_str()));
+    let content_type =
+        content_type.unwrap_or_else(|| client::dataset_content_type(Path::new(url.path())));
+    let response = client
+        .fetch_external(url.clone())
+        .await
+        .with_context(|| format!("Failed to download dataset from {url}"))?;
+
+    let Some(file_size) = response.content_length() else {
+        bail!(
+            "{url} did not report the size of the dataset, download it and upload the file instead"
+        );
+    };
+
+    if file_size > client::MIN_CHUNK_SIZE_BYTES {
+        let key = slugify(name);
+        let stream = client.chunked_upload_dataset_from_response(
+            project,
+            name,
+            &key,
+            response,
+            file_size,
+            content_type,
+            concurrency,
+        )?;
+        let id = track_upload_progress(stream).await?;
+
+        Ok(UploadedDataset { id, key: Some(key) })
+    } else {
+        let response = with_spinner("Uploading Dataset", async {
+            let data = response.bytes().await?.to_vec();
+            Ok::<_, anyhow::Error>(
+                client
+                    .upload_dataset_bytes(project, name, file_name, data, content_type)
+                    .await?,
+            )
+        })
+        .await?;
+
+        Ok(UploadedDataset {
+            id: response.id,
+            key: response.key,
+        })
+    }
+}
+
+async fn upload_dataset<P: AsRef<Path> + Sync>(
+    client: &AdaptiveClient,
+    project: &str,
+    dataset: P,
+    name: Option<String>,
+    content_type: Option<String>,
+    concurrency: usize,
+    restart: bool,
+) -> std::result::Result<(), anyhow::Error> {
+    let resume = if restart {
+        Resume::Restart
+    } else {
+        Resume::Continue
+    };
+
+    let uploaded = if dataset.as_ref() == Path::new("-") {
+        let name =
+            name.ok_or_else(|| anyhow!("--name is required when reading the dataset from stdin"))?;
+        let mut file = tempfile::Builder::new()
+            .prefix(&format!("{}-", slugify(&name)))
+            .tempfile()?;
+        io::copy(&mut io::stdin().lock(), &mut file).context("Failed to read stdin")?;
+        let path = file.into_temp_path();
+
+        upload_dataset_file(
+            client,
+            project,
+            &path,
+            &name,
+            content_type.as_deref(),
+            concurrency,
+            Resume::Never,
+        )
+        .await?
+    } else {
+        let name = name.unwrap_or_else(|| default_dataset_name(dataset.as_ref()));
+        upload_dataset_file(
+            client,
+            project,
+            &dataset,
+            &name,
+            content_type.as_deref(),
+            concurrency,
+            resume,
+        )
+        .await?
+    };
+
+    if io::stdout().is_terminal() {
+        println!(
+            "Dataset uploaded successfully with ID: {}, key: {}",
+            uploaded.id,
+            uploaded.key.unwrap_or("<none>".to_string())
+        );
+    } else {
+        println!("{}", uploaded.id);
     }
-    terminal::set_progress(terminal::Progress::None);
     terminal::send_notification("Dataset upload complete");
 
     Ok(())
 }
 
-async fn print_schema(client: &AdaptiveClient, project: String, recipe: String) -> Result<()> {
+#[derive(Serialize)]
+struct DatasetOutput {
+    id: Uuid,
+    key: Option<String>,
+    name: String,
+    records: Option<i64>,
+    created_at: String,
+}
+
+async fn list_datasets(client: &AdaptiveClient, project: &str, output: OutputFormat) -> Result<()> {
+    let datasets = client.list_datasets(project).await?;
+
+    if output == OutputFormat::Json {
+        let datasets: Vec<DatasetOutput> = datasets
+            .into_iter()
+            .map(|dataset| DatasetOutput {
+                id: dataset.id,
+                key: dataset.key,
+                name: dataset.name,
+                records: dataset.records,
+                created_at: dataset.created_at,
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&datasets)?);
+        return Ok(());
+    }
+
+    element!(DatasetList(datasets: datasets)).print();
+    Ok(())
+}
+
+/// Where to save a dataset by default. A dataset named after its file keeps that name, otherwise
+/// the extension of the download URL is added, falling back to JSONL.
+fn dataset_download_path(name: &str, download_url: &str) -> PathBuf {
+    let name = name.replace(['/', '\\'], "_");
+
+    // Datasets uploaded without a name are called `<file name>-<unix secs>`
+    let file_name = match name.rsplit_once('-') {
+        Some((file_name, secs)) if !secs.is_empty() && secs.bytes().all(|b| b.is_ascii_digit()) => {
+            file_name
+        }
+        _ => &name,
+    };
+    if client::dataset_extension(Path::new(file_name)).is_some() {
+        return PathBuf::from(file_name);
+    }
+
+    let url_path = match Url::parse(download_url) {
+        Ok(url) => url.path().to_string(),
+        Err(_) => download_url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    };
+    let extension = client::dataset_extension(Path::new(&url_path))
+        .unwrap_or(client::DEFAULT_DATASET_EXTENSION);
+    PathBuf::from(format!("{name}.{extension}"))
+}
+
+async fn download_dataset(
+    client: &AdaptiveClient,
+    project: &str,
+    dataset: &str,
+    path: Option<PathBuf>,
+) -> Result<()> {
+    let dataset = client
+        .get_dataset(project, dataset)
+        .await?
+        .ok_or_else(|| anyhow!("Dataset not found"))?;
+    let path = path.unwrap_or_else(|| dataset_download_path(&dataset.name, &dataset.download_url));
+
+    track_download_progress(client.download_file_with_progress(&dataset.download_url, &path))
+        .await?;
+
+    if io::stdout().is_terminal() {
+        println!("Dataset {} downloaded to {}", dataset.id, path.display());
+    } else {
+        println!("{}", path.display());
+    }
+    terminal::send_notification("Dataset download complete");
+
+    Ok(())
+}
+
+async fn print_schema(
+    client: &AdaptiveClient,
+    project: String,
+    recipe: String,
+    format: SchemaFormat,
+) -> Result<()> {
     let recipe = client
         .get_recipe(project, recipe)
         .await?
         .ok_or_else(|| anyhow!("Recipe not found"))?;
-    let output = highlight(
-        &serde_json::to_string_pretty(&recipe.json_schema)?,
-        Options {
-            formatter: FormatterOption::Terminal {
-                theme: Some(themes::get("ayu_light").expect("Syntax highlighting theme not found")),
+
+    match format {
+        SchemaFormat::Pretty => {}
+        SchemaFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&recipe.json_schema)?);
+            return Ok(());
+        }
+        SchemaFormat::Yaml => {
+            print!("{}", serde_yaml_ng::to_string(&recipe.json_schema)?);
+            return Ok(());
+        }
+    }
+
+    let schema_json = serde_json::to_string_pretty(&recipe.json_schema)?;
+    if ui::color_enabled() {
+        let output = highlight(
+            &schema_json,
+            Options {
+                formatter: FormatterOption::Terminal {
+                    theme: Some(
+                        themes::get("ayu_light").expect("Syntax highlighting theme not found"),
+                    ),
+                },
+                lang_or_file: Some("json"),
             },
-            lang_or_file: Some("json"),
-        },
-    );
-    println!("{}", output);
+        );
+        println!("{}", output);
+    } else {
+        println!("{}", schema_json);
+    }
+
+    if let Ok(schema) = serde_json::from_value::<JsonSchema>(recipe.json_schema) {
+        let mut restricted: Vec<(String, Vec<String>)> = schema
+            .properties
+            .into_iter()
+            .filter_map(|(name, property)| match property {
+                JsonSchemaPropertyContents::Regular(regular) => {
+                    regular.allowed_values().map(|allowed| (name, allowed))
+                }
+                JsonSchemaPropertyContents::Union(_) => None,
+            })
+            .collect();
+        restricted.sort();
+
+        if !restricted.is_empty() {
+            element!(AllowedValues(properties: restricted)).print();
+        }
+    }
     Ok(())
 }
 
-async fn list_models(client: &AdaptiveClient, project: String) -> Result<()> {
+#[derive(Serialize)]
+struct ModelOutput {
+    id: String,
+    key: Option<String>,
+    name: String,
+    status: String,
+}
+
+impl ModelOutput {
+    fn new(model: &dyn ModelDisplay) -> Self {
+        let key = model.get_key();
+        ModelOutput {
+            id: model.get_id(),
+            key: (!key.is_empty()).then(|| key.to_string()),
+            name: model.get_name().to_string(),
+            status: model.get_status(),
+        }
+    }
+}
+
+fn print_models_json(models: Vec<ModelOutput>) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&models)?);
+    Ok(())
+}
+
+async fn list_models(client: &AdaptiveClient, project: String, output: OutputFormat) -> Result<()> {
     let model_services = client.list_models(project).await?;
+    if output == OutputFormat::Json {
+        return print_models_json(model_services.iter().map(|m| ModelOutput::new(m)).collect());
+    }
     element!(ModelsList(model_services: model_services)).print();
     Ok(())
 }
 
-async fn list_all_models(client: &AdaptiveClient) -> Result<()> {
+async fn list_all_models(client: &AdaptiveClient, output: OutputFormat) -> Result<()> {
     let models = client.list_all_models().await?;
+    if output == OutputFormat::Json {
+        return print_models_json(models.iter().map(|m| ModelOutput::new(m)).collect());
+    }
     element!(AllModelsList(models: models)).print();
     Ok(())
 }
 
-async fn cancel_job(client: &AdaptiveClient, id: Uuid) -> Result<()> {
-    let cancelled = client.cancel_job(id).await?;
-    println!("Job {} cancelled successfully", cancelled.id);
+#[derive(Serialize)]
+struct CancelResult {
+    id: Uuid,
+    cancelled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CancelSummary {
+    jobs: Vec<CancelResult>,
+    cancelled: usize,
+    failed: usize,
+}
+
+async fn cancel_jobs(client: &AdaptiveClient, ids: Vec<Uuid>, output: OutputFormat) -> Result<()> {
+    let jobs: Vec<CancelResult> = join_all(ids.into_iter().map(|id| async move {
+        match client.cancel_job(id).await {
+            Ok(cancelled) => CancelResult {
+                id: cancelled.id,
+                cancelled: true,
+                error: None,
+            },
+            Err(e) => CancelResult {
+                id,
+                cancelled: false,
+                error: Some(e.to_string()),
+            },
+        }
+    }))
+    .await;
+
+    let cancelled = jobs.iter().filter(|job| job.cancelled).count();
+    let failed = jobs.len() - cancelled;
+
+    match output {
+        OutputFormat::Json => {
+            let summary = CancelSummary {
+                jobs,
+                cancelled,
+                failed,
+            };
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+        }
+        OutputFormat::Pretty => {
+            for job in &jobs {
+                match &job.error {
+                    None => println!("Job {} cancelled successfully", job.id),
+                    Some(error) => eprintln!("Failed to cancel job {}: {}", job.id, error),
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!(
+            "Failed to cancel {} of {} job(s)",
+            failed,
+            cancelled + failed
+        );
+    }
+
     Ok(())
 }
 
+async fn cancel_all_jobs(
+    client: &AdaptiveClient,
+    project: Option<String>,
+    yes: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let filter = JobFilter {
+        project,
+        statuses: job_statuses(&[], false),
+        ..Default::default()
+    };
+    let jobs = client.list_jobs(&filter, None).await?;
+
+    if jobs.is_empty() {
+        if output == OutputFormat::Pretty {
+            println!("No running or pending jobs to cancel");
+        }
+        return cancel_jobs(client, Vec::new(), output).await;
+    }
+
+    if !yes {
+        if !io::stdin().is_terminal() {
+            bail!(
+                "Refusing to cancel {} job(s) without confirmation, pass --yes to cancel them",
+                jobs.len()
+            );
+        }
+        eprint!("Cancel {} running or pending job(s)? [y/N] ", jobs.len());
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+            bail!("Aborted, no jobs were cancelled");
+        }
+    }
+
+    cancel_jobs(client, jobs.into_iter().map(|job| job.id).collect(), output).await
+}
+
 async fn get_job(client: Arc<AdaptiveClient>, job_id: Uuid, follow: bool) -> Result<()> {
     if follow {
         element! {
@@ -536,16 +1494,128 @@ async fn get_job(client: Arc<AdaptiveClient>, job_id: Uuid, follow: bool) -> Res
     Ok(())
 }
 
-async fn list_recipes(client: &AdaptiveClient, project: &str) -> Result<()> {
+const MAX_SUMMARY_PARAMS: usize = 4;
+const MAX_CONCURRENT_FETCHES: usize = 8;
+
+fn schema_type_abbreviation(property: &JsonSchemaPropertyContents) -> &str {
+    match property {
+        JsonSchemaPropertyContents::Regular(regular) => match regular.type_.as_str() {
+            "integer" => "int",
+            "string" => "str",
+            "number" => "num",
+            "boolean" => "bool",
+            other => other,
+        },
+        JsonSchemaPropertyContents::Union(_) => "json",
+    }
+}
+
+fn required_params_summary(schema: &JsonSchema) -> String {
+    let mut params: Vec<String> = schema
+        .required
+        .iter()
+        .take(MAX_SUMMARY_PARAMS)
+        .map(|name| match schema.properties.get(name) {
+            Some(property) => format!("{}:{}", name, schema_type_abbreviation(property)),
+            None => name.clone(),
+        })
+        .collect();
+    if schema.required.len() > MAX_SUMMARY_PARAMS {
+        params.push("…".to_string());
+    }
+    format!("[{}]", params.join(", "))
+}
+
+async fn recipe_params_summary(
+    client: &AdaptiveClient,
+    project: &str,
+    recipe: &GetCustomRecipesCustomRecipes,
+) -> String {
+    let Some(key) = recipe.key.clone() else {
+        return String::new();
+    };
+    let schema = client
+        .get_recipe(project.to_string(), key)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|recipe| serde_json::from_value::<JsonSchema>(recipe.json_schema).ok());
+    match schema {
+        Some(schema) => required_params_summary(&schema),
+        None => "?".to_string(),
+    }
+}
+
+#[derive(Serialize)]
+struct RecipeOutput {
+    id: Uuid,
+    key: Option<String>,
+    name: String,
+    description: String,
+    updated_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parameters: Option<String>,
+}
+
+async fn list_recipes(
+    client: &AdaptiveClient,
+    project: &str,
+    show_params: bool,
+    output: OutputFormat,
+) -> Result<()> {
     let recipes = client.list_recipes(project).await?;
 
-    element!(RecipeList(recipes: recipes)).print();
+    let params = if show_params {
+        Some(
+            futures::stream::iter(&recipes)
+                .map(|recipe| recipe_params_summary(client, project, recipe))
+                .buffered(MAX_CONCURRENT_FETCHES)
+                .collect::<Vec<_>>()
+                .await,
+        )
+    } else {
+        None
+    };
+
+    if output == OutputFormat::Json {
+        let recipes: Vec<RecipeOutput> = recipes
+            .into_iter()
+            .enumerate()
+            .map(|(i, recipe)| RecipeOutput {
+                id: recipe.id,
+                key: recipe.key,
+                name: recipe.name,
+                description: recipe.description,
+                updated_at: humantime::format_rfc3339_seconds(
+                    recipe.updated_at.unwrap_or(recipe.created_at).0,
+                )
+                .to_string(),
+                parameters: params.as_ref().map(|params| params[i].clone()),
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&recipes)?);
+        return Ok(());
+    }
+
+    element!(RecipeList(recipes: recipes, params: params)).print();
 
     Ok(())
 }
 
+/// The directory a local recipe lives in, used to find its `pyproject.toml`.
+fn recipe_source_dir(recipe: &Path) -> PathBuf {
+    if recipe.is_dir() {
+        return recipe.to_path_buf();
+    }
+    match recipe.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
 fn zip_recipe_dir<P: AsRef<Path>>(recipe_dir: P) -> Result<TempPath> {
     if recipe_dir.as_ref().join("main.py").is_file() {
+        let pyproject = PyProject::load(recipe_dir.as_ref())?;
         let tmp_file = NamedTempFile::new()?;
 
         {
@@ -555,7 +1625,7 @@ fn zip_recipe_dir<P: AsRef<Path>>(recipe_dir: P) -> Result<TempPath> {
             zip_file.create_from_directory_with_options(
                 &recipe_dir.as_ref().to_owned(),
                 |_| options,
-                &ZipIgnoreEntryHandler::new(),
+                &PyProjectEntryHandler::new(pyproject),
             )?;
         }
 
@@ -565,6 +1635,124 @@ fn zip_recipe_dir<P: AsRef<Path>>(recipe_dir: P) -> Result<TempPath> {
     }
 }
 
+/// --project wins, then pyproject.toml (per recipe, then the whole Python project), then the
+/// configured default.
+fn resolve_project(
+    maybe_project: Option<String>,
+    pyproject_dir: &Path,
+    recipe_key: Option<&str>,
+    default_project: Option<String>,
+) -> Result<Option<String>> {
+    if maybe_project.is_some() {
+        return Ok(maybe_project);
+    }
+    let pyproject = PyProject::load(pyproject_dir)?;
+    Ok(pyproject
+        .project(recipe_key)
+        .map(str::to_owned)
+        .or(default_project))
+}
+
+fn require_project(project: Option<String>) -> Result<String> {
+    project.ok_or(anyhow!(
+        "A project must be specified via the --project argument, pyproject.toml or a default project configured"
+    ))
+}
+
+fn recipe_name_and_key(
+    recipe: &Path,
+    name: Option<String>,
+    key: Option<String>,
+) -> (String, String) {
+    let name = name.unwrap_or_else(|| recipe.file_name().unwrap().to_string_lossy().into_owned());
+    let key = key.unwrap_or_else(|| slugify(&name));
+    (name, key)
+}
+
+const LARGE_RECIPE_FILE_BYTES: u64 = 100 * 1024 * 1024;
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+fn preview_recipe(
+    project: &str,
+    name: Option<String>,
+    key: Option<String>,
+    recipe: &Path,
+) -> Result<()> {
+    let (name, key) = recipe_name_and_key(recipe, name, key);
+
+    let files = if recipe.is_dir() {
+        if !recipe.join("main.py").is_file() {
+            bail!("Recipe directory must contain a main.py file");
+        }
+        pyproject::packaged_files(recipe)?
+    } else {
+        let file_name = recipe.file_name().unwrap_or(recipe.as_os_str());
+        vec![(PathBuf::from(file_name), fs::metadata(recipe)?.len())]
+    };
+
+    println!("Recipe {name} (key: {key}) would be published to project {project}");
+    let file_width = files
+        .iter()
+        .map(|(path, _)| path.display().to_string().chars().count() as u32)
+        .max()
+        .unwrap_or_default()
+        + 2;
+    let config = ListConfig {
+        columns: vec![
+            Column {
+                header: "File",
+                width: Some(file_width),
+            },
+            Column {
+                header: "Size",
+                width: Some(12),
+            },
+        ],
+        empty_message: "No files would be published",
+    };
+    let rows = files
+        .iter()
+        .map(|(path, size)| {
+            vec![
+                Cell::from(path.display().to_string()),
+                Cell::from(format_size(*size)),
+            ]
+        })
+        .collect();
+    let mut el: AnyElement<'static> = render_list(config, rows).into();
+    el.print();
+
+    let total: u64 = files.iter().map(|(_, size)| size).sum();
+    println!("{} file(s), {} in total", files.len(), format_size(total));
+
+    for (path, size) in files
+        .iter()
+        .filter(|(_, size)| *size > LARGE_RECIPE_FILE_BYTES)
+    {
+        element!(ErrorMessage(message: format!(
+            "Warning: {} is {}, add it to ignore_files in pyproject.toml or a .zipignore file if it should not be published",
+            path.display(),
+            format_size(*size)
+        )))
+        .eprint();
+    }
+
+    Ok(())
+}
+
 async fn publish_recipe<P: AsRef<Path>>(
     client: &AdaptiveClient,
     project: &str,
@@ -573,15 +1761,7 @@ async fn publish_recipe<P: AsRef<Path>>(
     recipe: P,
     force: bool,
 ) -> Result<()> {
-    let name = name.unwrap_or_else(|| {
-        recipe
-            .as_ref()
-            .file_name()
-            .unwrap()
-            .to_string_lossy()
-            .into_owned()
-    });
-    let key = key.unwrap_or_else(|| slugify(&name));
+    let (name, key) = recipe_name_and_key(recipe.as_ref(), name, key);
 
     let existing = client.get_recipe(project.to_string(), key.clone()).await?;
 
@@ -634,15 +1814,134 @@ async fn publish_recipe<P: AsRef<Path>>(
     Ok(())
 }
 
+fn recipe_archive_entries(path: &Path, fallback_name: &str) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut entries = BTreeMap::new();
+
+    let Ok(mut archive) = zip::ZipArchive::new(fs::File::open(path)?) else {
+        // Single file recipes are not archived
+        entries.insert(fallback_name.to_string(), fs::read(path)?);
+        return Ok(entries);
+    };
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        let mut contents = Vec::new();
+        file.read_to_end(&mut contents)?;
+        entries.insert(file.name().to_string(), contents);
+    }
+
+    Ok(entries)
+}
+
+fn print_line_diff(old: &str, new: &str) {
+    const MAX_DIFF_CELLS: usize = 25_000_000;
+
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    if old.len() * new.len() > MAX_DIFF_CELLS {
+        println!("  (file too large to show line differences)");
+        return;
+    }
+
+    // Longest common subsequence lengths of the remaining lines
+    let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            println!("  -{}", old[i]);
+            i += 1;
+        } else {
+            println!("  +{}", new[j]);
+            j += 1;
+        }
+    }
+}
+
+async fn diff_recipe(
+    client: &AdaptiveClient,
+    project: &str,
+    recipe: &str,
+    path: &Path,
+    lines: bool,
+) -> Result<()> {
+    let published = client
+        .get_recipe(project.to_string(), recipe.to_string())
+        .await?
+        .ok_or_else(|| anyhow!("Recipe not found"))?;
+    let download_url = published
+        .download_url
+        .ok_or_else(|| anyhow!("Recipe {} has no downloadable archive", recipe))?;
+
+    let fallback_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or("main.py".to_string());
+
+    let published_archive = NamedTempFile::new()?.into_temp_path();
+    client
+        .download_file_to_path(&download_url, &published_archive)
+        .await?;
+    let published_entries = recipe_archive_entries(&published_archive, &fallback_name)?;
+
+    // Archive the local copy the same way publish does, so the same files are ignored
+    let local_entries = if path.is_dir() {
+        recipe_archive_entries(&zip_recipe_dir(path)?, &fallback_name)?
+    } else {
+        recipe_archive_entries(path, &fallback_name)?
+    };
+
+    let mut different = false;
+    for name in published_entries
+        .keys()
+        .chain(local_entries.keys())
+        .collect::<BTreeSet<_>>()
+    {
+        match (published_entries.get(name), local_entries.get(name)) {
+            (Some(_), None) => println!("removed:  {}", name),
+            (None, Some(_)) => println!("added:    {}", name),
+            (Some(old), Some(new)) if old != new => {
+                println!("modified: {}", name);
+                if lines && let (Ok(old), Ok(new)) = (str::from_utf8(old), str::from_utf8(new)) {
+                    print_line_diff(old, new);
+                }
+            }
+            _ => continue,
+        }
+        different = true;
+    }
+
+    if !different && io::stdout().is_terminal() {
+        println!("No differences between {} and {}", recipe, path.display());
+    }
+
+    Ok(())
+}
+
 fn recipe_key_completer(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
     let mut completions = vec![];
     let Some(current) = current.to_str() else {
         return completions;
     };
 
-    let config = config::read_config().expect("Failed to read config");
+    let config = config::read_config(false).expect("Failed to read config");
 
-    let client = AdaptiveClient::new(config.adaptive_base_url, config.adaptive_api_key);
+    let client = client_from_config(&config);
 
     let handle = Handle::current();
     let recipes = handle
@@ -660,15 +1959,67 @@ fn recipe_key_completer(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
     completions
 }
 
+fn client_from_config(config: &config::Config) -> AdaptiveClient {
+    let mut client = AdaptiveClient::new(
+        config.adaptive_base_url.clone(),
+        config.adaptive_api_key.clone(),
+    );
+    if let Some(graphql_url) = &config.adaptive_graphql_url {
+        client = client.with_graphql_url(graphql_url.clone());
+    }
+    if let Some(rest_url) = &config.adaptive_rest_url {
+        client = client.with_rest_base_url(rest_url.clone());
+    }
+    if let Some(mock_dir) = &config.mock_dir {
+        client = client.with_mock_dir(mock_dir.clone());
+    }
+    client.with_timeouts(
+        config.request_timeout_secs.map_or(
+            client::DEFAULT_REQUEST_TIMEOUT,
+            std::time::Duration::from_secs,
+        ),
+        config.connect_timeout_secs.map_or(
+            client::DEFAULT_CONNECT_TIMEOUT,
+            std::time::Duration::from_secs,
+        ),
+    )
+}
+
+fn dataset_key_completer(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
+    let mut completions = vec![];
+    let Some(current) = current.to_str() else {
+        return completions;
+    };
+
+    let config = config::read_config(false).expect("Failed to read config");
+
+    let client = client_from_config(&config);
+
+    let handle = Handle::current();
+    let datasets = handle
+        .block_on(client.list_datasets(&config.default_project.expect("No default project set")))
+        .unwrap();
+
+    datasets.into_iter().for_each(|dataset| {
+        if let Some(key) = dataset.key
+            && key.starts_with(current)
+        {
+            completions.push(CompletionCandidate::new(key));
+        }
+    });
+
+    completions
+}
+
 fn project_completer(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
     let mut completions = vec![];
     let Some(current) = current.to_str() else {
         return completions;
     };
 
-    let config = config::read_config().expect("Failed to read config");
+    let config = config::read_config(false).expect("Failed to read config");
 
-    let client = AdaptiveClient::new(config.adaptive_base_url, config.adaptive_api_key);
+    let client = client_from_config(&config);
 
     let handle = Handle::current();
     let projects = handle.block_on(client.list_projects()).unwrap();
@@ -688,9 +2039,9 @@ fn pool_completer(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
         return completions;
     };
 
-    let config = config::read_config().expect("Failed to read config");
+    let config = config::read_config(false).expect("Failed to read config");
 
-    let client = AdaptiveClient::new(config.adaptive_base_url, config.adaptive_api_key);
+    let client = client_from_config(&config);
 
     let handle = Handle::current();
     let pools = handle.block_on(client.list_pools()).unwrap();
@@ -704,39 +2055,82 @@ fn pool_completer(current: &std::ffi::OsStr) -> Vec<CompletionCandidate> {
     completions
 }
 
+fn is_dataset_property(property: &RegularJsonSchemaPropertyContents) -> bool {
+    property.type_ == "string" && property.format.as_deref() == Some("dataset")
+}
+
+async fn recipe_schema(client: &AdaptiveClient, project: &str, recipe: &str) -> Result<JsonSchema> {
+    let recipe_contents = client
+        .get_recipe(project.to_string(), recipe.to_string())
+        .await?
+        .ok_or_else(|| anyhow!("Recipe not found"))?;
+    serde_json::from_value(recipe_contents.json_schema)
+        .map_err(|e| anyhow!("Failed to parse JSON schema: {e}"))
+}
+
 async fn parse_recipe_args(
     client: &AdaptiveClient,
     project: &str,
     recipe: String,
+    schema: JsonSchema,
     args: Vec<String>,
+    provided: &Map<String, Value>,
 ) -> Result<Map<String, Value>> {
-    let recipe_contents = client
-        .get_recipe(project.to_string(), recipe.clone())
-        .await?
-        .ok_or_else(|| anyhow!("Recipe not found"))?;
-    let schema = recipe_contents.json_schema;
-    let schema: JsonSchema =
-        serde_json::from_value(schema).map_err(|e| anyhow!("Failed to parse JSON schema: {e}"))?;
-
     let expected_args = schema
         .properties
         .iter()
         .map(|(name, value)| match value {
             JsonSchemaPropertyContents::Regular(regular_json_schema_property_contents) => {
+                let help = if is_dataset_property(regular_json_schema_property_contents) {
+                    format!(
+                        "{} (use @<path> to upload a local file)",
+                        regular_json_schema_property_contents.description
+                    )
+                } else {
+                    regular_json_schema_property_contents.description.clone()
+                };
                 let base = Arg::new(name)
-                    .required(schema.required.contains(name))
-                    .help(regular_json_schema_property_contents.description.clone())
+                    .required(schema.required.contains(name) && !provided.contains_key(name))
+                    .help(help)
                     .long(name);
 
                 match regular_json_schema_property_contents.type_.as_str() {
+                    "array" => {
+                        let item_type = regular_json_schema_property_contents
+                            .items
+                            .as_ref()
+                            .map(|items| items.type_.as_str())
+                            .ok_or_else(|| {
+                                anyhow!("No item type specified in schema for array {name}")
+                            })?;
+                        let base = base.num_args(1..).action(ArgAction::Append);
+                        match item_type {
+                            "integer" => Ok(base.value_parser(value_parser!(i64))),
+                            "string" => Ok(base.value_parser(value_parser!(String))),
+                            "boolean" => Ok(base.value_parser(value_parser!(bool))),
+                            "number" => Ok(base.value_parser(value_parser!(f64))),
+                            unknown => Err(anyhow!(
+                                "Unknown item type {unknown} specified in schema for array {name}"
+                            )),
+                        }
+                    }
                     "integer" => Ok(base.value_parser(value_parser!(i64))),
-                    "string" => Ok(base.value_parser(value_parser!(String))),
+                    "string" => match regular_json_schema_property_contents.allowed_values() {
+                        Some(allowed) => Ok(base.value_parser(PossibleValuesParser::new(allowed))),
+                        None => Ok(base.value_parser(value_parser!(String))),
+                    },
                     "boolean" => Ok(base.value_parser(value_parser!(bool))),
                     "number" => Ok(base.value_parser(value_parser!(f64))),
-                    unknown => Err(anyhow!("Unknown type {unknown} specified in schema")),
+                    "object" => Ok(base.value_parser(parse_json_object)),
+                    unknown => Err(anyhow!(
+                        "Unknown type {unknown} specified in schema for {name}"
+                    )),
                 }
             }
-            JsonSchemaPropertyContents::Union(_) => Ok(Arg::new(name).required(true).long(name)),
+            JsonSchemaPropertyContents::Union(_) => Ok(Arg::new(name)
+                .required(!provided.contains_key(name))
+                .long(name)
+                .value_parser(parse_json_value)),
         })
         .collect::<Result<Vec<_>>>()?;
 
@@ -764,7 +2158,43 @@ async fn parse_recipe_args(
                     }
                     "string" => {
                         if let Some(value) = parsed_args.get_one::<String>(&name) {
-                            let v = serde_json::to_value(value).unwrap();
+                            let v = match value.strip_prefix('@') {
+                                Some(path)
+                                    if is_dataset_property(
+                                        &regular_json_schema_property_contents,
+                                    ) =>
+                                {
+                                    let path = Path::new(path);
+                                    let uploaded = upload_dataset_file(
+                                        client,
+                                        project,
+                                        path,
+                                        &default_dataset_name(path),
+                                        None,
+                                        client::DEFAULT_UPLOAD_CONCURRENCY,
+                                        Resume::Continue,
+                                    )
+                                    .await
+                                    .with_context(|| {
+                                        format!(
+                                            "Failed to upload {} for parameter {}",
+                                            path.display(),
+                                            name
+                                        )
+                                    })?;
+                                    if io::stdout().is_terminal() {
+                                        println!(
+                                            "Uploaded {} as dataset {}",
+                                            path.display(),
+                                            uploaded.id
+                                        );
+                                    }
+                                    Value::String(
+                                        uploaded.key.unwrap_or_else(|| uploaded.id.to_string()),
+                                    )
+                                }
+                                _ => serde_json::to_value(value).unwrap(),
+                            };
                             parameters.insert(name.clone(), v);
                         }
                     }
@@ -780,15 +2210,42 @@ async fn parse_recipe_args(
                             parameters.insert(name.clone(), v);
                         }
                     }
+                    "array" => {
+                        let item_type = regular_json_schema_property_contents
+                            .items
+                            .as_ref()
+                            .map(|items| items.type_.as_str());
+                        let values: Option<Vec<Value>> = match item_type {
+                            Some("integer") => parsed_args
+                                .get_many::<i64>(&name)
+                                .map(|values| values.map(|value| Value::from(*value)).collect()),
+                            Some("string") => parsed_args.get_many::<String>(&name).map(|values| {
+                                values.map(|value| Value::from(value.clone())).collect()
+                            }),
+                            Some("boolean") => parsed_args
+                                .get_many::<bool>(&name)
+                                .map(|values| values.map(|value| Value::from(*value)).collect()),
+                            Some("number") => parsed_args
+                                .get_many::<f64>(&name)
+                                .map(|values| values.map(|value| Value::from(*value)).collect()),
+                            _ => None,
+                        };
+                        if let Some(values) = values {
+                            parameters.insert(name.clone(), Value::Array(values));
+                        }
+                    }
+                    "object" => {
+                        if let Some(value) = parsed_args.get_one::<Value>(&name) {
+                            parameters.insert(name.clone(), value.clone());
+                        }
+                    }
 
                     _ => (),
                 }
             }
             JsonSchemaPropertyContents::Union(_) => {
-                if let Some(value) = parsed_args.get_one::<String>(&name) {
-                    //FIXME so provide a arg validator that checks for json
-                    let v = serde_json::from_str(value).unwrap();
-                    parameters.insert(name.clone(), v);
+                if let Some(value) = parsed_args.get_one::<Value>(&name) {
+                    parameters.insert(name.clone(), value.clone());
                 }
             }
         }
@@ -796,21 +2253,82 @@ async fn parse_recipe_args(
     Ok(parameters)
 }
 
-async fn run_recipe(client: &AdaptiveClient, project: &str, run_args: RunArgs) -> Result<()> {
-    let parameters = if let Some(parameters_file) = run_args.parameters {
-        let content = fs::read_to_string(&parameters_file)?;
-        serde_json::from_str(&content).map_err(|e| {
-            anyhow!(
-                "Failed to parse parameters: {e} from file {}",
-                parameters_file.clone().to_str().unwrap()
-            )
-        })?
-    } else if run_args.recipe.is_empty() {
-        Map::new()
+fn parse_json_value(value: &str) -> std::result::Result<Value, String> {
+    serde_json::from_str(value).map_err(|e| format!("invalid JSON: {e}"))
+}
+
+fn parse_json_object(value: &str) -> std::result::Result<Value, String> {
+    match parse_json_value(value)? {
+        object @ Value::Object(_) => Ok(object),
+        other => Err(format!("expected a JSON object, got {other}")),
+    }
+}
+
+fn read_parameters(path: &Path) -> Result<Map<String, Value>> {
+    let (content, source) = if path == Path::new("-") {
+        (io::read_to_string(io::stdin())?, "stdin".to_string())
     } else {
-        parse_recipe_args(client, project, run_args.recipe.clone(), run_args.args).await?
+        (
+            fs::read_to_string(path)?,
+            format!("file {}", path.display()),
+        )
     };
 
+    if content.trim().is_empty() {
+        bail!("Empty parameters on {source}, expected a JSON object");
+    }
+
+    serde_json::from_str(&content)
+        .map_err(|e| anyhow!("Failed to parse parameters: {e} from {source}"))
+}
+
+fn merge_parameters(base: &mut Map<String, Value>, overrides: Map<String, Value>) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Object(existing)), Value::Object(nested)) => {
+                merge_parameters(existing, nested)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+async fn run_recipe(client: &AdaptiveClient, project: &str, run_args: RunArgs) -> Result<()> {
+    let mut parameters = Map::new();
+    for parameters_file in &run_args.parameters {
+        merge_parameters(&mut parameters, read_parameters(parameters_file)?);
+    }
+
+    if !run_args.recipe.is_empty() {
+        let schema = recipe_schema(client, project, &run_args.recipe).await?;
+
+        if run_args.parameters.is_empty() || !run_args.args.is_empty() {
+            let overrides = parse_recipe_args(
+                client,
+                project,
+                run_args.recipe.clone(),
+                schema.clone(),
+                run_args.args,
+                &parameters,
+            )
+            .await?;
+            merge_parameters(&mut parameters, overrides);
+        }
+
+        if !run_args.parameters.is_empty() {
+            let problems = schema.validate(&parameters);
+            if !problems.is_empty() {
+                bail!(
+                    "Invalid parameters for recipe {}:\n  {}",
+                    run_args.recipe,
+                    problems.join("\n  ")
+                );
+            }
+        }
+    }
+
     let response = client
         .run_recipe(
             project,
@@ -818,7 +2336,7 @@ async fn run_recipe(client: &AdaptiveClient, project: &str, run_args: RunArgs) -
             parameters,
             run_args.name,
             run_args.compute_pool,
-            run_args.gpus.unwrap_or(1),
+            run_args.gpus,
             false,
         )
         .await?;
@@ -829,6 +2347,110 @@ async fn run_recipe(client: &AdaptiveClient, project: &str, run_args: RunArgs) -
         println!("{}", response.id);
     }
 
+    if run_args.wait || run_args.follow {
+        let job = wait_for_job(Arc::new(client.clone()), response.id, run_args.follow).await?;
+
+        let succeeded = matches!(job.status, get_job::JobStatus::COMPLETED);
+        if let Some(output_dir) = run_args.output_dir {
+            fs::create_dir_all(&output_dir)?;
+            if succeeded {
+                download_job_artifacts(client, response.id, &output_dir).await?;
+            } else {
+                write_job_status(&job, &output_dir)?;
+            }
+        }
+
+        if !succeeded {
+            bail!("Job {} finished with status {}", response.id, job.status);
+        }
+    }
+
+    Ok(())
+}
+
+async fn wait_for_job(
+    client: Arc<AdaptiveClient>,
+    job_id: Uuid,
+    follow: bool,
+) -> Result<get_job::GetJobJob> {
+    if follow || io::stdout().is_terminal() {
+        element! {
+            ui::FollowJobStatus(client: Some(client.clone()), job_id: job_id)
+        }
+        .render_loop()
+        .await?;
+    }
+
+    loop {
+        let job = client.get_job(job_id).await?;
+        if !matches!(
+            job.status,
+            get_job::JobStatus::PENDING | get_job::JobStatus::RUNNING
+        ) {
+            return Ok(job);
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+    }
+}
+
+async fn download_job_artifacts(
+    client: &AdaptiveClient,
+    job_id: Uuid,
+    output_dir: &Path,
+) -> Result<()> {
+    let artifacts = client.get_job_artifacts(job_id).await?;
+    let mut file_names = BTreeSet::new();
+
+    for artifact in artifacts {
+        let Some(download_url) = artifact.download_url else {
+            continue;
+        };
+        let mut file_name = artifact.name.replace(['/', '\\'], "_");
+        if file_name.is_empty() {
+            file_name = artifact.id.to_string();
+        } else if file_names.contains(&file_name) {
+            // Several artifacts can share a name, keep them apart with their id
+            let name = Path::new(&file_name);
+            file_name = match (name.file_stem(), name.extension()) {
+                (Some(stem), Some(extension)) => format!(
+                    "{}-{}.{}",
+                    stem.to_string_lossy(),
+                    artifact.id,
+                    extension.to_string_lossy()
+                ),
+                _ => format!("{}-{}", file_name, artifact.id),
+            };
+        }
+        file_names.insert(file_name.clone());
+        let path = output_dir.join(file_name);
+        client
+            .download_file_to_path(&download_url, &path)
+            .await
+            .with_context(|| format!("Failed to download artifact {}", artifact.id))?;
+        println!("{}", path.display());
+    }
+
+    Ok(())
+}
+
+fn write_job_status(job: &get_job::GetJobJob, output_dir: &Path) -> Result<()> {
+    let mut status = format!("Job {} ({}): {}\n", job.name, job.id, job.status);
+    for stage in &job.stages {
+        status.push_str(&format!("Stage {}: {:?}\n", stage.name, stage.status));
+    }
+    if let Some(error) = &job.error {
+        status.push_str(&format!("\n{}\n", error));
+    }
+
+    let path = output_dir.join("job-status.txt");
+    fs::write(&path, status)?;
+    element!(ErrorMessage(message: format!(
+        "Job logs cannot be fetched from the API, the job's status and error were saved to {} instead",
+        path.display()
+    )))
+    .eprint();
+    println!("{}", path.display());
+
     Ok(())
 }
 
@@ -1174,10 +2796,243 @@ async fn remove_role_permission(
     Ok(())
 }
 
-async fn list_jobs(client: &AdaptiveClient, project: Option<String>) -> Result<()> {
-    let response = client.list_jobs(project).await?;
+const DEFAULT_JOBS_LIMIT: usize = 100;
+
+async fn list_jobs(
+    client: &AdaptiveClient,
+    filter: &JobFilter,
+    limit: Option<usize>,
+    group_by: Option<JobsGroupBy>,
+    output: OutputFormat,
+) -> Result<()> {
+    // Fetch one more job than shown to tell whether the list was cut short
+    let mut response = client
+        .list_jobs(filter, limit.map(|limit| limit + 1))
+        .await?;
+    if let Some(limit) = limit
+        && response.len() > limit
+    {
+        response.truncate(limit);
+        if output == OutputFormat::Pretty && io::stdout().is_terminal() {
+            eprintln!("Showing the {limit} most recent jobs, use --limit to show more");
+        }
+    }
+
+    let Some(group_by) = group_by else {
+        if output == OutputFormat::Json {
+            let jobs: Vec<JobOutput> = response.iter().map(JobOutput::new).collect();
+            println!("{}", serde_json::to_string_pretty(&jobs)?);
+        } else {
+            element!(JobsList(jobs: response)).print();
+        }
+        return Ok(());
+    };
+
+    let mut groups: BTreeMap<String, Vec<list_jobs::ListJobsJobsNodes>> = BTreeMap::new();
+    for job in response {
+        let group = match group_by {
+            JobsGroupBy::Status => job_status_name(&job.status),
+            JobsGroupBy::User => job
+                .created_by
+                .as_ref()
+                .map(|user| format!("{} <{}>", user.name, user.email))
+                .unwrap_or("Unknown".to_string()),
+            JobsGroupBy::Recipe => job
+                .recipe
+                .as_ref()
+                .map(|recipe| recipe.key.clone().unwrap_or(recipe.name.clone()))
+                .unwrap_or("No recipe".to_string()),
+        };
+        groups.entry(group).or_default().push(job);
+    }
+
+    if output == OutputFormat::Json {
+        let groups: BTreeMap<String, Vec<JobOutput>> = groups
+            .into_iter()
+            .map(|(group, jobs)| (group, jobs.iter().map(JobOutput::new).collect()))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&groups)?);
+        return Ok(());
+    }
+
+    if groups.is_empty() {
+        element!(JobsList(jobs: vec![])).print();
+    }
+    for (group, jobs) in groups {
+        println!("{} ({})", group, jobs.len());
+        element!(JobsList(jobs: jobs)).print();
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct JobOutput {
+    id: Uuid,
+    status: String,
+    kind: String,
+    created_at: String,
+    created_by: Option<String>,
+    duration_ms: Option<i64>,
+    recipe: Option<String>,
+}
+
+impl JobOutput {
+    fn new(job: &list_jobs::ListJobsJobsNodes) -> Self {
+        JobOutput {
+            id: job.id,
+            status: job_status_name(&job.status),
+            kind: ui::job_kind_name(&job.kind),
+            created_at: humantime::format_rfc3339(job.created_at.0).to_string(),
+            created_by: job.created_by.as_ref().map(|user| user.email.clone()),
+            duration_ms: job.duration_ms,
+            recipe: job
+                .recipe
+                .as_ref()
+                .map(|recipe| recipe.key.clone().unwrap_or(recipe.name.clone())),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JobsSnapshot {
+    generated_at: String,
+    counts: BTreeMap<String, usize>,
+    running: Vec<RunningJobSnapshot>,
+}
+
+#[derive(Serialize)]
+struct RunningJobSnapshot {
+    id: Uuid,
+    name: String,
+    created_by: Option<String>,
+    duration_ms: Option<i64>,
+    progress_percent: Option<f32>,
+}
+
+fn job_status_name(status: &list_jobs::JobStatus) -> String {
+    match status {
+        list_jobs::JobStatus::PENDING => "pending".to_string(),
+        list_jobs::JobStatus::RUNNING => "running".to_string(),
+        list_jobs::JobStatus::COMPLETED => "completed".to_string(),
+        list_jobs::JobStatus::FAILED => "failed".to_string(),
+        list_jobs::JobStatus::CANCELED => "canceled".to_string(),
+        list_jobs::JobStatus::Other(other) => other.to_lowercase(),
+    }
+}
+
+fn jobs_snapshot_to_prometheus(snapshot: &JobsSnapshot) -> String {
+    let mut out = String::new();
+    out.push_str("# HELP adpt_jobs Number of jobs by status\n");
+    out.push_str("# TYPE adpt_jobs gauge\n");
+    for (status, count) in &snapshot.counts {
+        out.push_str(&format!("adpt_jobs{{status=\"{}\"}} {}\n", status, count));
+    }
+    out.push_str("# HELP adpt_job_progress_percent Progress of running jobs\n");
+    out.push_str("# TYPE adpt_job_progress_percent gauge\n");
+    for job in &snapshot.running {
+        if let Some(progress) = job.progress_percent {
+            out.push_str(&format!(
+                "adpt_job_progress_percent{{id=\"{}\"}} {}\n",
+                job.id, progress
+            ));
+        }
+    }
+    out
+}
+
+fn write_atomically(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let mut tmp_file = NamedTempFile::new_in(dir)?;
+    tmp_file.write_all(contents)?;
+
+    // Temporary files are only readable by their owner, but exports are usually read by other
+    // users such as a metrics exporter, so keep the mode of the file being replaced or use 0644
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let permissions = fs::metadata(path)
+            .map(|metadata| metadata.permissions())
+            .unwrap_or_else(|_| fs::Permissions::from_mode(0o644));
+        tmp_file.as_file().set_permissions(permissions)?;
+    }
+
+    tmp_file
+        .persist(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+async fn jobs_snapshot(client: &AdaptiveClient, filter: &JobFilter) -> Result<JobsSnapshot> {
+    let jobs = client.list_jobs(filter, None).await?;
+
+    let mut counts = BTreeMap::new();
+    for job in &jobs {
+        *counts.entry(job_status_name(&job.status)).or_insert(0) += 1;
+    }
+
+    let running = try_join_all(
+        jobs.iter()
+            .filter(|job| matches!(job.status, list_jobs::JobStatus::RUNNING))
+            .map(|job| async move {
+                let details = client.get_job(job.id).await?;
+                Ok::<_, anyhow::Error>(RunningJobSnapshot {
+                    id: job.id,
+                    name: details.name,
+                    created_by: job.created_by.as_ref().map(|user| user.email.clone()),
+                    duration_ms: job.duration_ms,
+                    progress_percent: ui::stages_progress(&details.stages),
+                })
+            }),
+    )
+    .await?;
+
+    Ok(JobsSnapshot {
+        generated_at: humantime::format_rfc3339(SystemTime::now()).to_string(),
+        counts,
+        running,
+    })
+}
+
+async fn export_jobs(client: &AdaptiveClient, filter: &JobFilter, path: &Path) -> Result<()> {
+    let snapshot = jobs_snapshot(client, filter).await?;
+
+    let contents = if path.extension().is_some_and(|ext| ext == "prom") {
+        jobs_snapshot_to_prometheus(&snapshot)
+    } else {
+        serde_json::to_string_pretty(&snapshot)?
+    };
+    write_atomically(path, contents.as_bytes())?;
+
+    if io::stdout().is_terminal() {
+        println!("Jobs snapshot written to {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn generate_man_pages(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    let mut command = Cli::command();
+    command.build();
+    write_man_pages(&command, dir)
+}
+
+fn write_man_pages(command: &Command, dir: &Path) -> Result<()> {
+    let man = clap_mangen::Man::new(command.clone());
+    let mut file = fs::File::create(dir.join(man.get_filename()))?;
+    man.render(&mut file)?;
 
-    element!(JobsList(jobs: response)).print();
+    for subcommand in command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set() && subcommand.get_name() != "help")
+    {
+        write_man_pages(subcommand, dir)?;
+    }
 
     Ok(())
 }
@@ -1210,7 +3065,187 @@ fn read_input(prompt: &str, default: Option<&str>, description: Option<&str>) ->
     }
 }
 
-fn interactive_config() -> Result<()> {
+fn print_config_path() -> Result<()> {
+    let path = config::get_config_file_path()?;
+
+    if io::stdout().is_terminal() {
+        let state = if path.exists() {
+            "exists"
+        } else {
+            "does not exist yet"
+        };
+        println!("{} ({state})", path.display());
+    } else {
+        println!("{}", path.display());
+        if !path.exists() {
+            bail!("Config file {} does not exist", path.display());
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct WhoamiOutput {
+    profile: Option<String>,
+    config_file: PathBuf,
+    api_url: Url,
+    graphql_url: Option<Url>,
+    rest_url: Option<Url>,
+    default_project: Option<String>,
+    api_key_source: String,
+    user: Option<WhoamiUser>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct WhoamiUser {
+    id: Uuid,
+    name: String,
+    email: String,
+}
+
+async fn print_whoami(
+    client: &AdaptiveClient,
+    config: &config::Config,
+    output: OutputFormat,
+) -> Result<()> {
+    let user = match client.me().await {
+        Ok(Some(me)) => Ok(WhoamiUser {
+            id: me.id,
+            name: me.name,
+            email: me.email,
+        }),
+        Ok(None) => Err("Not authenticated, the API key was rejected".to_string()),
+        Err(e) if e.is_unauthenticated() => {
+            Err("Not authenticated, the API key was rejected".to_string())
+        }
+        Err(e) => Err(format!("Failed to get the current user: {e}")),
+    };
+
+    let whoami = WhoamiOutput {
+        profile: config.profile.clone(),
+        config_file: config::get_config_file_path()?,
+        api_url: config.adaptive_base_url.clone(),
+        graphql_url: config.adaptive_graphql_url.clone(),
+        rest_url: config.adaptive_rest_url.clone(),
+        default_project: config.default_project.clone(),
+        api_key_source: config.api_key_source.to_string(),
+        error: user.as_ref().err().cloned(),
+        user: user.ok(),
+    };
+
+    if output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&whoami)?);
+    } else {
+        print_whoami_pretty(&whoami);
+    }
+
+    // Fail so that scripts can check the configuration before going on
+    match whoami.error {
+        Some(error) => Err(anyhow!(error)),
+        None => Ok(()),
+    }
+}
+
+fn print_whoami_pretty(whoami: &WhoamiOutput) {
+    if let Some(profile) = &whoami.profile {
+        println!("Profile:         {}", profile);
+    }
+    println!("Config file:     {}", whoami.config_file.display());
+    println!("API URL:         {}", whoami.api_url);
+    if let Some(graphql_url) = &whoami.graphql_url {
+        println!("GraphQL URL:     {}", graphql_url);
+    }
+    if let Some(rest_url) = &whoami.rest_url {
+        println!("REST URL:        {}", rest_url);
+    }
+    println!(
+        "Default project: {}",
+        whoami.default_project.as_deref().unwrap_or("<none>")
+    );
+    println!("API key from:    {}", whoami.api_key_source);
+    if let Some(user) = &whoami.user {
+        println!("User:            {} <{}>", user.name, user.email);
+    }
+}
+
+async fn print_status(client: &AdaptiveClient, output: OutputFormat) -> Result<()> {
+    let filter = JobFilter {
+        statuses: job_statuses(&[], false),
+        ..Default::default()
+    };
+    let snapshot = jobs_snapshot(client, &filter).await?;
+
+    if output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&snapshot)?);
+        return Ok(());
+    }
+
+    if snapshot.counts.is_empty() {
+        println!("No running or pending jobs");
+        return Ok(());
+    }
+    println!(
+        "{}",
+        snapshot
+            .counts
+            .iter()
+            .map(|(status, count)| format!("{count} {status}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let config = ListConfig {
+        columns: vec![
+            Column {
+                header: "ID",
+                width: Some(38),
+            },
+            Column {
+                header: "Name",
+                width: Some(30),
+            },
+            Column {
+                header: "Progress",
+                width: Some(10),
+            },
+            Column {
+                header: "Duration",
+                width: None,
+            },
+        ],
+        empty_message: "No running jobs",
+    };
+    let rows: Vec<Vec<Cell>> = snapshot
+        .running
+        .iter()
+        .map(|job| {
+            vec![
+                Cell::from(job.id.to_string()),
+                Cell::from(job.name.as_str()),
+                Cell::from(
+                    job.progress_percent
+                        .map(|progress| format!("{progress:.0}%"))
+                        .unwrap_or("-".to_string()),
+                ),
+                Cell::from(
+                    humantime::format_duration(std::time::Duration::from_secs(
+                        job.duration_ms.unwrap_or_default() as u64 / 1000,
+                    ))
+                    .to_string(),
+                ),
+            ]
+        })
+        .collect();
+    let mut el: AnyElement<'static> = render_list(config, rows).into();
+    el.print();
+
+    Ok(())
+}
+
+fn interactive_config(no_keyring: bool) -> Result<()> {
     element!(ConfigHeader()).print();
 
     let adaptive_base_url = loop {
@@ -1229,19 +3264,23 @@ fn interactive_config() -> Result<()> {
         }
     };
 
-    let adaptive_api_key = loop {
-        let api_key = read_input(
-            "API Key",
-            None,
-            Some("Your Adaptive API key (stored securely in OS keyring)"),
-        )?;
+    let adaptive_api_key = if no_keyring {
+        None
+    } else {
+        Some(loop {
+            let api_key = read_input(
+                "API Key",
+                None,
+                Some("Your Adaptive API key (stored securely in OS keyring)"),
+            )?;
 
-        if api_key.is_empty() {
-            element!(ErrorMessage(message: "API key cannot be empty".to_string())).print();
-            println!();
-        } else {
-            break api_key;
-        }
+            if api_key.is_empty() {
+                element!(ErrorMessage(message: "API key cannot be empty".to_string())).print();
+                println!();
+            } else {
+                break api_key;
+            }
+        })
     };
 
     let default_project_str = read_input(
@@ -1255,12 +3294,18 @@ fn interactive_config() -> Result<()> {
         Some(default_project_str)
     };
 
-    config::set_api_key_keyring(adaptive_api_key)?;
+    match adaptive_api_key {
+        Some(api_key) => config::set_api_key_keyring(api_key)?,
+        None => println!(
+            "The OS keyring is disabled, provide your API key via the ADAPTIVE_API_KEY environment variable"
+        ),
+    }
 
-    let config_file = config::ConfigFile {
-        adaptive_base_url: Some(adaptive_base_url),
-        default_project,
-    };
+    let mut config_file = config::read_config_file()?;
+    let profile = config::selected_profile(&config_file);
+    let settings = config_file.settings_mut(profile.as_deref());
+    settings.adaptive_base_url = Some(adaptive_base_url);
+    settings.default_project = default_project;
 
     config::write_config(config_file)?;
 
@@ -1268,3 +3313,66 @@ fn interactive_config() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dataset_download_path_drops_default_name_suffix() {
+        assert_eq!(
+            dataset_download_path("data.jsonl-1760623672", "/datasets/ds"),
+            PathBuf::from("data.jsonl")
+        );
+        assert_eq!(
+            dataset_download_path("scores.csv-1760623672", "/datasets/ds"),
+            PathBuf::from("scores.csv")
+        );
+    }
+
+    #[test]
+    fn dataset_download_path_adds_known_extension() {
+        assert_eq!(
+            dataset_download_path("v1.2", "/datasets/ds"),
+            PathBuf::from("v1.2.jsonl")
+        );
+        assert_eq!(
+            dataset_download_path(
+                "train",
+                "https://storage.example.com/datasets/train.parquet?signature=abc"
+            ),
+            PathBuf::from("train.parquet")
+        );
+        assert_eq!(
+            dataset_download_path("eval/set", "/datasets/eval.CSV"),
+            PathBuf::from("eval_set.csv")
+        );
+    }
+
+    #[test]
+    fn zip_recipe_dir_leaves_out_ignored_files() {
+        let recipe_dir = tempfile::tempdir().unwrap();
+        let recipe_dir = recipe_dir.path();
+        fs::write(recipe_dir.join("main.py"), "print('hello')").unwrap();
+        fs::write(recipe_dir.join("main.cpython-312.pyc"), [0u8; 16]).unwrap();
+        fs::create_dir_all(recipe_dir.join("node_modules/left-pad")).unwrap();
+        fs::write(recipe_dir.join("node_modules/left-pad/index.js"), "").unwrap();
+        fs::create_dir(recipe_dir.join("utils")).unwrap();
+        fs::write(recipe_dir.join("utils/helpers.py"), "").unwrap();
+
+        let zip_path = zip_recipe_dir(recipe_dir).unwrap();
+        let archive = zip::ZipArchive::new(fs::File::open(&zip_path).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+
+        assert!(names.contains(&"main.py"), "{names:?}");
+        assert!(names.contains(&"utils/helpers.py"), "{names:?}");
+        assert!(
+            !names.iter().any(|name| name.ends_with(".pyc")),
+            "{names:?}"
+        );
+        assert!(
+            !names.iter().any(|name| name.starts_with("node_modules")),
+            "{names:?}"
+        );
+    }
+}
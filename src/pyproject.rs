@@ -0,0 +1,203 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use zip::{
+    ZipWriter,
+    result::ZipResult,
+    write::{FileOptionExtension, FileOptions, SimpleFileOptions},
+};
+use zip_extensions::{
+    entry_handler::EntryHandler, zip_ignore_entry_handler::ZipIgnoreEntryHandler,
+    zip_writer_extensions::ZipWriterExtensions,
+};
+
+pub const DEFAULT_IGNORE_FILES: &[&str] = &[
+    ".git",
+    ".venv",
+    "venv",
+    "__pycache__",
+    "node_modules",
+    ".mypy_cache",
+    ".pytest_cache",
+    ".ruff_cache",
+    ".DS_Store",
+];
+
+pub const DEFAULT_IGNORE_EXTENSIONS: &[&str] = &["pyc", "pyo"];
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PyProject {
+    #[serde(default)]
+    tool: Tool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Tool {
+    #[serde(default)]
+    adaptive: AdaptiveTool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AdaptiveTool {
+    #[serde(default, alias = "use-case")]
+    project: Option<String>,
+    #[serde(default)]
+    recipes: HashMap<String, RecipeTool>,
+    #[serde(default)]
+    ignore_files: Vec<String>,
+    #[serde(default)]
+    ignore_extensions: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RecipeTool {
+    #[serde(default, alias = "use-case")]
+    project: Option<String>,
+}
+
+impl PyProject {
+    /// Load `pyproject.toml` from the recipe directory, or its parent for recipes that live
+    /// inside a larger Python project.
+    pub fn load(recipe_dir: &Path) -> Result<Self> {
+        let candidates = [Some(recipe_dir), recipe_dir.parent()];
+        for dir in candidates.into_iter().flatten() {
+            let path = dir.join("pyproject.toml");
+            if path.is_file() {
+                let contents = fs::read_to_string(&path)?;
+                return toml::from_str(&contents)
+                    .with_context(|| format!("Failed to parse {}", path.display()));
+            }
+        }
+
+        Ok(Self::default())
+    }
+
+    /// The project configured for a recipe key, falling back to the one set for the whole
+    /// Python project.
+    pub fn project(&self, recipe_key: Option<&str>) -> Option<&str> {
+        recipe_key
+            .and_then(|key| self.tool.adaptive.recipes.get(key))
+            .and_then(|recipe| recipe.project.as_deref())
+            .or(self.tool.adaptive.project.as_deref())
+    }
+
+    pub fn ignore_files(&self) -> impl Iterator<Item = &str> {
+        DEFAULT_IGNORE_FILES
+            .iter()
+            .copied()
+            .chain(self.tool.adaptive.ignore_files.iter().map(String::as_str))
+    }
+
+    pub fn ignore_extensions(&self) -> impl Iterator<Item = &str> {
+        DEFAULT_IGNORE_EXTENSIONS.iter().copied().chain(
+            self.tool
+                .adaptive
+                .ignore_extensions
+                .iter()
+                .map(|extension| extension.trim_start_matches('.')),
+        )
+    }
+
+    /// Whether a path relative to the recipe directory should be left out of the recipe.
+    pub fn should_ignore(&self, relative_path: &Path) -> bool {
+        let ignored_component = relative_path.components().any(|component| {
+            self.ignore_files()
+                .any(|name| component.as_os_str() == name)
+        });
+        let ignored_extension = relative_path
+            .extension()
+            .is_some_and(|extension| self.ignore_extensions().any(|ignored| extension == ignored));
+
+        ignored_component || ignored_extension
+    }
+}
+
+/// Skips entries ignored by `pyproject.toml` before applying `.zipignore` files.
+pub struct PyProjectEntryHandler<H = ZipIgnoreEntryHandler> {
+    pyproject: PyProject,
+    inner: H,
+}
+
+impl PyProjectEntryHandler {
+    pub fn new(pyproject: PyProject) -> Self {
+        Self::with_inner(pyproject, ZipIgnoreEntryHandler::new())
+    }
+}
+
+impl<H> PyProjectEntryHandler<H> {
+    pub fn with_inner(pyproject: PyProject, inner: H) -> Self {
+        Self { pyproject, inner }
+    }
+}
+
+impl<T: FileOptionExtension, H: EntryHandler<T>> EntryHandler<T> for PyProjectEntryHandler<H> {
+    fn handle_entry<W: io::Write + io::Seek>(
+        &self,
+        writer: &mut ZipWriter<W>,
+        root: &PathBuf,
+        entry_path: &PathBuf,
+        file_options: FileOptions<T>,
+        buffer: &mut Vec<u8>,
+    ) -> ZipResult<()> {
+        let relative_path = entry_path.strip_prefix(root).unwrap_or(entry_path);
+        if self.pyproject.should_ignore(relative_path) {
+            return Ok(());
+        }
+        self.inner
+            .handle_entry(writer, root, entry_path, file_options, buffer)
+    }
+}
+
+/// Records the files that reach it instead of writing them to the archive.
+#[derive(Default, Clone)]
+struct FileRecorder {
+    files: Arc<Mutex<Vec<(PathBuf, u64)>>>,
+}
+
+impl<T: FileOptionExtension> EntryHandler<T> for FileRecorder {
+    fn handle_entry<W: io::Write + io::Seek>(
+        &self,
+        _writer: &mut ZipWriter<W>,
+        root: &PathBuf,
+        entry_path: &PathBuf,
+        _file_options: FileOptions<T>,
+        _buffer: &mut Vec<u8>,
+    ) -> ZipResult<()> {
+        let metadata = fs::metadata(entry_path)?;
+        if metadata.is_file() {
+            let relative_path = entry_path.strip_prefix(root).unwrap_or(entry_path);
+            self.files
+                .lock()
+                .unwrap()
+                .push((relative_path.to_path_buf(), metadata.len()));
+        }
+        Ok(())
+    }
+}
+
+/// The files that publishing a recipe directory would package, with their sizes, going
+/// through the same ignore rules without writing an archive.
+pub fn packaged_files(recipe_dir: &Path) -> Result<Vec<(PathBuf, u64)>> {
+    let recorder = FileRecorder::default();
+    let handler = PyProjectEntryHandler::with_inner(
+        PyProject::load(recipe_dir)?,
+        ZipIgnoreEntryHandler::with_inner(recorder.clone()),
+    );
+
+    let mut writer = ZipWriter::new(io::Cursor::new(Vec::new()));
+    writer.create_from_directory_with_options(
+        &recipe_dir.to_path_buf(),
+        |_| SimpleFileOptions::default(),
+        &handler,
+    )?;
+
+    let mut files = recorder.files.lock().unwrap().clone();
+    files.sort();
+    Ok(files)
+}
@@ -1,16 +1,33 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use adaptive_client_rust::get_custom_recipes::GetCustomRecipesCustomRecipes;
-use adaptive_client_rust::get_job::{GetJobJobStages, GetJobJobStagesInfo, JobStatusOutput};
-use adaptive_client_rust::list_all_models::{self, ListAllModelsModels};
-use adaptive_client_rust::list_jobs::{self, ListJobsJobsNodes};
-use adaptive_client_rust::list_models::{self, ListModelsProjectModelServices};
-use adaptive_client_rust::{AdaptiveClient, get_job};
 use iocraft::prelude::*;
 use tokio::sync::watch::Receiver;
 use uuid::Uuid;
 
+use crate::client::get_custom_recipes::GetCustomRecipesCustomRecipes;
+use crate::client::get_job::{GetJobJobStages, GetJobJobStagesInfo, JobStatusOutput};
+use crate::client::list_all_models::{self, ListAllModelsModels};
+use crate::client::list_datasets::ListDatasetsDatasets;
+use crate::client::list_jobs::{self, ListJobsJobsNodes};
+use crate::client::list_models::{self, ListModelsProjectModelServices};
+use crate::client::{AdaptiveClient, get_job};
+
+/// Whether output is colored. crossterm, which renders all components, already leaves
+/// colors out when NO_COLOR is set, so this also covers the `--no-color` flag.
+pub fn color_enabled() -> bool {
+    !crossterm::style::Colored::ansi_color_disabled_memoized()
+}
+
+pub fn disable_color() {
+    crossterm::style::force_color_output(false);
+}
+
+/// A status glyph, or a plain ASCII marker when colors are disabled.
+fn glyph(fancy: &'static str, plain: &'static str) -> &'static str {
+    if color_enabled() { fancy } else { plain }
+}
+
 pub struct Cell {
     pub content: String,
     pub color: Option<Color>,
@@ -199,7 +216,13 @@ pub fn ProgressBar(mut hooks: Hooks, props: &ProgressBarProps) -> impl Into<AnyE
         View {
             Text(content: props.title.clone())
             View(margin_left: 1, margin_right: 1, border_style: BorderStyle::Single, border_edges: Edges::Left | Edges::Right, border_color: Color::Blue, width: 60) {
-                View(width: Percent(progress.get()), height: 1, background_color: Color::Green)
+                #(if color_enabled() {
+                    element!(View(width: Percent(progress.get()), height: 1, background_color: Color::Green))
+                } else {
+                    element!(View(width: Percent(progress.get()), height: 1, overflow: Overflow::Hidden) {
+                        Text(content: "#".repeat(58), wrap: TextWrap::NoWrap)
+                    })
+                })
             }
             View() {
                 Text(content: format!("{:.0}%", progress))
@@ -211,21 +234,168 @@ pub fn ProgressBar(mut hooks: Hooks, props: &ProgressBarProps) -> impl Into<AnyE
 #[derive(Default, Props)]
 pub struct RecipeListProps {
     pub recipes: Vec<GetCustomRecipesCustomRecipes>,
+    pub params: Option<Vec<String>>,
 }
 
 #[component]
 pub fn RecipeList(props: &RecipeListProps) -> impl Into<AnyElement<'static>> {
-    let config = ListConfig {
-        columns: vec![Column {
+    let name_width = props
+        .recipes
+        .iter()
+        .map(|recipe| recipe.name.chars().count() as u32)
+        .max()
+        .unwrap_or_default()
+        + 1;
+    let mut columns = vec![
+        Column {
             header: "Name",
+            width: Some(name_width),
+        },
+        Column {
+            header: "Key",
+            width: Some(25),
+        },
+        Column {
+            header: "Id",
+            width: Some(38),
+        },
+        Column {
+            header: "Updated",
+            width: Some(22),
+        },
+    ];
+    if props.params.is_some() {
+        columns.push(Column {
+            header: "Parameters",
             width: None,
-        }],
+        });
+    }
+    let config = ListConfig {
+        columns,
         empty_message: "No recipes found",
     };
     let rows: Vec<Vec<Cell>> = props
         .recipes
         .iter()
-        .map(|recipe| vec![Cell::from(recipe.name.as_str())])
+        .enumerate()
+        .map(|(i, recipe)| {
+            let updated_at = recipe.updated_at.as_ref().unwrap_or(&recipe.created_at);
+            let mut row = vec![
+                Cell::from(recipe.name.as_str()),
+                Cell::from(recipe.key.as_deref().unwrap_or("N/A")),
+                Cell::from(recipe.id.to_string()),
+                Cell {
+                    content: humantime::format_rfc3339_seconds(updated_at.0).to_string(),
+                    color: Some(Color::DarkGrey),
+                },
+            ];
+            if let Some(params) = &props.params {
+                row.push(Cell {
+                    content: params[i].clone(),
+                    color: Some(Color::DarkGrey),
+                });
+            }
+            row
+        })
+        .collect();
+    render_list(config, rows)
+}
+
+#[derive(Default, Props)]
+pub struct AllowedValuesProps {
+    pub properties: Vec<(String, Vec<String>)>,
+}
+
+#[component]
+pub fn AllowedValues(props: &AllowedValuesProps) -> impl Into<AnyElement<'static>> {
+    let name_width = props
+        .properties
+        .iter()
+        .map(|(name, _)| name.chars().count() as u32)
+        .max()
+        .unwrap_or_default()
+        + 1;
+    let config = ListConfig {
+        columns: vec![
+            Column {
+                header: "Parameter",
+                width: Some(name_width),
+            },
+            Column {
+                header: "Allowed values",
+                width: None,
+            },
+        ],
+        empty_message: "No restricted parameters",
+    };
+    let rows: Vec<Vec<Cell>> = props
+        .properties
+        .iter()
+        .map(|(name, allowed)| {
+            vec![
+                Cell::from(name.as_str()),
+                Cell {
+                    content: allowed.join(", "),
+                    color: Some(Color::Green),
+                },
+            ]
+        })
+        .collect();
+    render_list(config, rows)
+}
+
+#[derive(Default, Props)]
+pub struct DatasetListProps {
+    pub datasets: Vec<ListDatasetsDatasets>,
+}
+
+#[component]
+pub fn DatasetList(props: &DatasetListProps) -> impl Into<AnyElement<'static>> {
+    let config = ListConfig {
+        columns: vec![
+            Column {
+                header: "Id",
+                width: Some(38),
+            },
+            Column {
+                header: "Name",
+                width: Some(25),
+            },
+            Column {
+                header: "Key",
+                width: Some(25),
+            },
+            Column {
+                header: "Records",
+                width: Some(10),
+            },
+            Column {
+                header: "Created",
+                width: None,
+            },
+        ],
+        empty_message: "No datasets found",
+    };
+    let rows: Vec<Vec<Cell>> = props
+        .datasets
+        .iter()
+        .map(|dataset| {
+            vec![
+                Cell::from(dataset.id.to_string()),
+                Cell::from(dataset.name.as_str()),
+                Cell::from(dataset.key.as_deref().unwrap_or("N/A")),
+                Cell::from(
+                    dataset
+                        .records
+                        .map(|records| records.to_string())
+                        .unwrap_or_default(),
+                ),
+                Cell {
+                    content: dataset.created_at.clone(),
+                    color: Some(Color::DarkGrey),
+                },
+            ]
+        })
         .collect();
     render_list(config, rows)
 }
@@ -238,33 +408,41 @@ pub struct JobsListProps {
 fn job_status_cell(status: &list_jobs::JobStatus) -> Cell {
     match status {
         list_jobs::JobStatus::PENDING => Cell {
-            content: "⏳".to_string(),
+            content: glyph("⏳", "..").to_string(),
             color: Some(Color::Yellow),
         },
         list_jobs::JobStatus::RUNNING => Cell {
-            content: "▶️".to_string(),
+            content: glyph("▶️", ">").to_string(),
             color: Some(Color::Yellow),
         },
         list_jobs::JobStatus::COMPLETED => Cell {
-            content: "✅".to_string(),
+            content: glyph("✅", "ok").to_string(),
             color: Some(Color::Green),
         },
         list_jobs::JobStatus::FAILED => Cell {
-            content: "❌".to_string(),
+            content: glyph("❌", "x").to_string(),
             color: Some(Color::Red),
         },
         list_jobs::JobStatus::CANCELED => Cell {
-            content: "🚫".to_string(),
+            content: glyph("🚫", "-").to_string(),
             color: Some(Color::Yellow),
         },
-        list_jobs::JobStatus::Other(_) => Cell {
-            content: "❓".to_string(),
+        list_jobs::JobStatus::Other(other) => Cell {
+            content: format!("{} {}", glyph("❓", "?"), other),
             color: Some(Color::Yellow),
         },
     }
 }
 
-trait ModelDisplay {
+pub fn job_kind_name(kind: &list_jobs::JobKind) -> String {
+    match kind {
+        list_jobs::JobKind::CUSTOM => "custom".to_string(),
+        list_jobs::JobKind::INTERACTIVE => "interactive".to_string(),
+        list_jobs::JobKind::Other(other) => other.to_lowercase(),
+    }
+}
+
+pub trait ModelDisplay {
     fn get_status(&self) -> String;
     fn get_id(&self) -> String;
     fn get_name(&self) -> &str;
@@ -324,11 +502,7 @@ impl ModelDisplay for ListAllModelsModels {
     }
 
     fn get_key(&self) -> &str {
-        if self.key.is_empty() {
-            "N/A"
-        } else {
-            &self.key
-        }
+        &self.key
     }
 }
 
@@ -361,7 +535,11 @@ fn model_to_row(model: &dyn ModelDisplay) -> Vec<Cell> {
         Cell::from(model.get_status()),
         Cell::from(model.get_id()),
         Cell::from(model.get_name()),
-        Cell::from(model.get_key()),
+        Cell::from(if model.get_key().is_empty() {
+            "N/A"
+        } else {
+            model.get_key()
+        }),
     ]
 }
 
@@ -395,16 +573,29 @@ pub fn AllModelsList(props: &AllModelsListProps) -> impl Into<AnyElement<'static
 
 #[component]
 pub fn JobsList(props: &JobsListProps) -> impl Into<AnyElement<'static>> {
+    let status_width = props
+        .jobs
+        .iter()
+        .filter_map(|job| match &job.status {
+            // The status glyph is two columns wide, followed by a space
+            list_jobs::JobStatus::Other(other) => Some(other.chars().count() as u32 + 3),
+            _ => None,
+        })
+        .fold(6, u32::max);
     let config = ListConfig {
         columns: vec![
             Column {
                 header: "Status",
-                width: Some(6),
+                width: Some(status_width),
             },
             Column {
                 header: "Id",
                 width: Some(36),
             },
+            Column {
+                header: "Kind",
+                width: Some(12),
+            },
             Column {
                 header: "Duration",
                 width: Some(8),
@@ -424,6 +615,7 @@ pub fn JobsList(props: &JobsListProps) -> impl Into<AnyElement<'static>> {
             vec![
                 job_status_cell(&job.status),
                 Cell::from(job.id.to_string()),
+                Cell::from(job_kind_name(&job.kind)),
                 Cell::from(
                     humantime::format_duration(Duration::from_millis(
                         job.duration_ms.unwrap_or_default() as u64,
@@ -472,6 +664,22 @@ fn get_common_stage_info(stage: &GetJobJobStagesInfo) -> CommonJobFields {
     }
 }
 
+pub fn stages_progress(stages: &[GetJobJobStages]) -> Option<f32> {
+    let (processed, total) = stages
+        .iter()
+        .filter_map(|stage| stage.info.as_ref().map(get_common_stage_info))
+        .filter_map(|info| Some((info.processed_num_samples?, info.total_num_samples?)))
+        .fold((0, 0), |(processed, total), (p, t)| {
+            (processed + p, total + t)
+        });
+
+    if total > 0 {
+        Some(processed as f32 / total as f32 * 100.0)
+    } else {
+        None
+    }
+}
+
 #[derive(Default, Props)]
 pub struct FollowJobStatusProps {
     pub client: Option<Arc<AdaptiveClient>>,
@@ -544,7 +752,7 @@ fn StatusIcon(props: &StatusIconProps) -> impl Into<AnyElement<'static>> {
         Some(JobStatusOutput::PENDING) => element! {
             Text (
                 color: Color::Reset,
-                content: "◇"
+                content: glyph("◇", "o")
             )
         }
         .into_any(),
@@ -555,28 +763,35 @@ fn StatusIcon(props: &StatusIconProps) -> impl Into<AnyElement<'static>> {
         Some(JobStatusOutput::DONE) => element! {
             Text (
                 color: Color::Green,
-                content: "◆"
+                content: glyph("◆", "*")
             )
         }
         .into_any(),
         Some(JobStatusOutput::CANCELLED) => element! {
             Text (
                 color: Color::Red,
-                content: "■"
+                content: glyph("■", "-")
             )
         }
         .into_any(),
         Some(JobStatusOutput::ERROR) => element! {
             Text (
                 color: Color::Red,
-                content: "▲"
+                content: glyph("▲", "!")
+            )
+        }
+        .into_any(),
+        Some(JobStatusOutput::Other(ref other)) => element! {
+            Text (
+                color: Color::Yellow,
+                content: format!("{} {}", glyph("❓", "?"), other)
             )
         }
         .into_any(),
-        _ => element! {
+        None => element! {
             Text (
                 color: Color::Yellow,
-                content: "❓"
+                content: glyph("❓", "?")
             )
         }
         .into_any(),
@@ -666,7 +881,11 @@ pub fn Spinner(props: &SpinnerProps, mut hooks: Hooks) -> impl Into<AnyElement<'
         }
     });
 
-    let spinner_chars = ["◐", "◓", "◑", "◒"];
+    let spinner_chars = if color_enabled() {
+        ["◐", "◓", "◑", "◒"]
+    } else {
+        ["|", "/", "-", "\\"]
+    };
     let current_char = spinner_chars[*frame.read()];
     let color = props.color.unwrap_or(Color::Cyan);
 
@@ -683,7 +902,7 @@ pub fn ConfigHeader(_props: &ConfigHeaderProps) -> impl Into<AnyElement<'static>
     element! {
         View(flex_direction: FlexDirection::Column, margin_bottom: 2) {
             Text(
-                content: "⚙️  Configure adpt",
+                content: format!("{}Configure adpt", glyph("⚙️  ", "")),
                 weight: Weight::Bold,
                 color: Color::Blue
             )
@@ -740,7 +959,7 @@ pub struct ErrorMessageProps {
 pub fn ErrorMessage(props: &ErrorMessageProps) -> impl Into<AnyElement<'static>> {
     element! {
         Text(
-            content: format!("✗ {}", props.message),
+            content: format!("{} {}", glyph("✗", "x"), props.message),
             color: Color::Red
         )
     }
@@ -756,7 +975,7 @@ pub fn SuccessMessage(props: &SuccessMessageProps) -> impl Into<AnyElement<'stat
     element! {
         View(margin_top: 1) {
             Text(
-                content: format!("✓ {}", props.message),
+                content: format!("{} {}", glyph("✓", "ok"), props.message),
                 weight: Weight::Bold,
                 color: Color::Green
             )
@@ -0,0 +1,85 @@
+use std::{
+    fs,
+    hash::Hasher,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::{client::UploadSession, config};
+
+/// Where the session of an interrupted chunked upload is kept so it can be resumed.
+///
+/// The state is keyed by the absolute path, size and modification time of the file, so
+/// any change to the file starts a fresh upload.
+pub struct UploadState {
+    path: PathBuf,
+}
+
+impl UploadState {
+    pub fn for_file(file: &Path) -> Result<Self> {
+        let metadata = fs::metadata(file).context("Failed to get file metadata")?;
+        let modified = metadata
+            .modified()?
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let file = fs::canonicalize(file)?;
+
+        // FNV-1a, which unlike the std hasher is stable across releases
+        let mut hasher = FnvHasher::default();
+        hasher.write(file.as_os_str().as_encoded_bytes());
+        hasher.write_u64(metadata.len());
+        hasher.write_u128(modified.as_nanos());
+
+        let dir = config::get_config_file_path()?
+            .parent()
+            .ok_or(anyhow!("Unable to determine the config directory"))?
+            .join("uploads");
+        Ok(Self {
+            path: dir.join(format!("{:016x}.json", hasher.finish())),
+        })
+    }
+
+    /// The saved session, if any. Unreadable state is ignored so the upload starts over.
+    pub fn load(&self) -> Option<UploadSession> {
+        let state = fs::read_to_string(&self.path).ok()?;
+        serde_json::from_str(&state).ok()
+    }
+
+    pub fn save(&self, session: &UploadSession) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_string(session)?)?;
+        Ok(())
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
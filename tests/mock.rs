@@ -0,0 +1,85 @@
+//! Runs `adpt` commands against the canned API responses in `tests/fixtures/mock`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+const JOB_ID: &str = "0190c7a4-0000-7000-8000-000000000001";
+const CHUNKED_DATASET_ID: &str = "0190c7a4-0000-7000-8000-000000000004";
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mock")
+}
+
+/// Run adpt in an empty directory with its own config location, so that neither the
+/// environment nor files of the user running the tests are picked up.
+fn adpt(dir: &TempDir, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_adpt"))
+        .current_dir(dir.path())
+        .arg("--mock-dir")
+        .arg(fixtures())
+        .arg("--config")
+        .arg(dir.path().join("config.toml"))
+        .args(args)
+        .env_remove("ADAPTIVE_API_KEY")
+        .env_remove("ADAPTIVE_BASE_URL")
+        .env_remove("ADPT_PROFILE")
+        .env_remove("DEFAULT_PROJECT")
+        .output()
+        .expect("Failed to run adpt")
+}
+
+#[test]
+fn jobs_are_listed_from_mock_responses() {
+    let dir = TempDir::new().unwrap();
+
+    let output = adpt(&dir, &["--output", "json", "jobs", "--project", "p"]);
+
+    assert!(output.status.success(), "{output:?}");
+    let jobs: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(jobs[0]["id"], JOB_ID);
+    assert_eq!(jobs[0]["status"], "running");
+}
+
+#[test]
+fn chunked_upload_completes_against_mock_session() {
+    let dir = TempDir::new().unwrap();
+    let dataset = dir.path().join("data.jsonl");
+    let line = format!("{{\"text\": \"{}\"}}\n", "a".repeat(1000));
+    fs::write(&dataset, line.repeat(6 * 1024)).unwrap();
+
+    let output = adpt(
+        &dir,
+        &[
+            "upload",
+            "--project",
+            "p",
+            "--name",
+            "data",
+            "--no-preflight",
+            dataset.to_str().unwrap(),
+        ],
+    );
+
+    assert!(output.status.success(), "{output:?}");
+    // The progress bar may be drawn before the id
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.trim_end().ends_with(CHUNKED_DATASET_ID), "{stdout}");
+    let uploads = dir.path().join("uploads");
+    assert!(!uploads.exists() || fs::read_dir(uploads).unwrap().next().is_none());
+}
+
+#[test]
+fn missing_mock_response_is_reported() {
+    let dir = TempDir::new().unwrap();
+
+    let output = adpt(&dir, &["models", "--project", "p"]);
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No mock response found"));
+}